@@ -0,0 +1,247 @@
+//! Credential lookup and storage for credentials saved by `linear auth login`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, Profile};
+use crate::error::{Error, Result};
+use crate::oauth::OAuthToken;
+use crate::time;
+
+/// A credential saved for a profile.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Credential {
+    ApiKey { api_key: String },
+    OAuth(OAuthToken),
+}
+
+impl Credential {
+    /// Value of the `Authorization` header for this credential.
+    pub fn authorization(&self) -> String {
+        match self {
+            Credential::ApiKey { api_key } => api_key.clone(),
+            Credential::OAuth(token) => format!("Bearer {}", token.access_token),
+        }
+    }
+
+    /// A masked, human-readable description of the credential.
+    pub fn describe(&self) -> String {
+        match self {
+            Credential::ApiKey { api_key } => format!("API key {}", mask(api_key)),
+            Credential::OAuth(token) => match token.expires_at {
+                Some(expires_at) if expires_at > time::now() => format!(
+                    "OAuth token {} (expires in {} min)",
+                    mask(&token.access_token),
+                    (expires_at - time::now()) / 60
+                ),
+                Some(_) => format!("OAuth token {} (expired)", mask(&token.access_token)),
+                None => format!("OAuth token {}", mask(&token.access_token)),
+            },
+        }
+    }
+}
+
+/// Where credentials saved by `linear auth login` are kept.
+pub trait CredentialStore {
+    /// A short description of the backend, shown by `linear auth status`.
+    fn describe(&self) -> String;
+    fn load(&self, profile: &str) -> Result<Option<Credential>>;
+    fn save(&self, profile: &str, credential: &Credential) -> Result<()>;
+    /// Remove a profile's credential, returning whether one existed.
+    fn delete(&self, profile: &str) -> Result<bool>;
+}
+
+/// The credential store in use.
+pub fn store() -> Result<Box<dyn CredentialStore>> {
+    Ok(Box::new(FileStore::new(
+        config::dir()?.join("credentials.toml"),
+    )))
+}
+
+/// Stores credentials in a TOML file readable only by the current user.
+pub struct FileStore {
+    path: PathBuf,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct CredentialsFile {
+    #[serde(default)]
+    profiles: BTreeMap<String, Credential>,
+}
+
+impl FileStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn read(&self) -> Result<CredentialsFile> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(CredentialsFile::default())
+            }
+            Err(e) => {
+                return Err(Error::io(
+                    format!("failed to read {}", self.path.display()),
+                    e,
+                ))
+            }
+        };
+        toml::from_str(&contents)
+            .map_err(|e| Error::Config(format!("invalid {}: {}", self.path.display(), e)))
+    }
+
+    fn write(&self, file: &CredentialsFile) -> Result<()> {
+        let contents = toml::to_string_pretty(file)
+            .map_err(|e| Error::Config(format!("failed to serialize credentials: {}", e)))?;
+        if let Some(parent) = self.path.parent() {
+            create_private_dir(parent)?;
+        }
+        write_private(&self.path, contents.as_bytes())
+            .map_err(|e| Error::io(format!("failed to write {}", self.path.display()), e))
+    }
+}
+
+impl CredentialStore for FileStore {
+    fn describe(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn load(&self, profile: &str) -> Result<Option<Credential>> {
+        Ok(self.read()?.profiles.remove(profile))
+    }
+
+    fn save(&self, profile: &str, credential: &Credential) -> Result<()> {
+        let mut file = self.read()?;
+        file.profiles
+            .insert(profile.to_string(), credential.clone());
+        self.write(&file)
+    }
+
+    fn delete(&self, profile: &str) -> Result<bool> {
+        let mut file = self.read()?;
+        let existed = file.profiles.remove(profile).is_some();
+        if existed {
+            self.write(&file)?;
+        }
+        Ok(existed)
+    }
+}
+
+pub(crate) fn create_private_dir(dir: &Path) -> Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder
+        .create(dir)
+        .map_err(|e| Error::io(format!("failed to create {}", dir.display()), e))
+}
+
+/// Write `contents` to `path`, restricting it to the current user (0600).
+pub(crate) fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // `mode` only applies to newly created files
+        if path.exists() {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    options.open(path)?.write_all(contents)
+}
+
+/// Where the credential in use came from.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KeySource {
+    Env,
+    Config,
+    Command,
+    Store,
+}
+
+impl KeySource {
+    pub fn describe(self) -> &'static str {
+        match self {
+            KeySource::Env => "LINEAR_API_KEY environment variable",
+            KeySource::Config => "api_key in config profile",
+            KeySource::Command => "api_key_command in config profile",
+            KeySource::Store => "credential store",
+        }
+    }
+}
+
+/// The credential to authenticate with, in order of precedence:
+/// `LINEAR_API_KEY`, the profile's `api_key`, its `api_key_command`, and
+/// finally the key or token saved by `linear auth login`.
+pub fn credential(profile: &Profile) -> Result<(Credential, KeySource)> {
+    let api_key = |api_key| Credential::ApiKey { api_key };
+    if let Ok(key) = std::env::var("LINEAR_API_KEY") {
+        return Ok((api_key(key), KeySource::Env));
+    }
+    if let Some(key) = &profile.api_key {
+        return Ok((api_key(key.clone()), KeySource::Config));
+    }
+    if let Some(command) = &profile.api_key_command {
+        return Ok((api_key(run_key_command(command)?), KeySource::Command));
+    }
+    match store()?.load(&profile.name)? {
+        Some(credential) => Ok((credential, KeySource::Store)),
+        None => Err(Error::MissingApiKey),
+    }
+}
+
+/// Which source outranking the credential store is set for `profile`,
+/// without reading it, so `api_key_command` isn't run.
+pub fn overriding_source(profile: &Profile) -> Option<KeySource> {
+    if std::env::var("LINEAR_API_KEY").is_ok() {
+        Some(KeySource::Env)
+    } else if profile.api_key.is_some() {
+        Some(KeySource::Config)
+    } else if profile.api_key_command.is_some() {
+        Some(KeySource::Command)
+    } else {
+        None
+    }
+}
+
+fn run_key_command(command: &str) -> Result<String> {
+    let output = if cfg!(windows) {
+        Command::new("cmd").args(["/C", command]).output()
+    } else {
+        Command::new("sh").args(["-c", command]).output()
+    }
+    .map_err(|e| Error::Config(format!("failed to run api_key_command: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(Error::Config(format!(
+            "api_key_command exited with {}",
+            output.status
+        )));
+    }
+    let key = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if key.is_empty() {
+        return Err(Error::Config("api_key_command printed nothing".to_string()));
+    }
+    Ok(key)
+}
+
+/// Hide all but the last four characters of a secret.
+pub fn mask(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    let visible: String = chars[chars.len().saturating_sub(4)..].iter().collect();
+    format!("****{}", visible)
+}
@@ -0,0 +1,201 @@
+//! On-disk cache of slow-changing workspace metadata (teams, workflow states,
+//! users, labels and projects), so name resolution and listing them doesn't
+//! cost a round trip every time.
+//!
+//! Entries are JSON files under `$XDG_CACHE_HOME/linear/<profile>` (falling
+//! back to `~/.cache/linear`), one per entity kind, or per team for workflow
+//! states. Each kind has its own time to live.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::time::now;
+
+/// How long each kind of entry is served before it is fetched again.
+const TTLS: &[(&str, Duration)] = &[
+    ("teams", Duration::from_secs(24 * 60 * 60)),
+    ("states", Duration::from_secs(24 * 60 * 60)),
+    ("users", Duration::from_secs(24 * 60 * 60)),
+    ("labels", Duration::from_secs(60 * 60)),
+    ("projects", Duration::from_secs(60 * 60)),
+];
+
+/// Whether the cache is read and written.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Mode {
+    /// Serve fresh entries, fetching and storing stale or missing ones
+    #[default]
+    Use,
+    /// Always fetch, and store the result
+    Refresh,
+    /// Neither read nor write the cache
+    Off,
+}
+
+/// The cache of one profile.
+pub struct Cache {
+    dir: PathBuf,
+    mode: Mode,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Entry<T> {
+    /// Seconds since the Unix epoch
+    fetched_at: u64,
+    data: T,
+}
+
+/// A summary of one cache entry, shown by `linear cache status`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryStatus {
+    pub key: String,
+    pub items: usize,
+    /// Seconds since the entry was fetched
+    pub age: u64,
+    pub fresh: bool,
+}
+
+/// Root directory holding every profile's cache.
+pub fn root() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_CACHE_HOME").filter(|d| !d.is_empty()) {
+        return Ok(PathBuf::from(dir).join("linear"));
+    }
+    dirs::home_dir()
+        .map(|home| home.join(".cache").join("linear"))
+        .ok_or_else(|| Error::Config("could not determine home directory".to_string()))
+}
+
+impl Cache {
+    pub fn new(profile: &str, mode: Mode) -> Result<Self> {
+        Ok(Self {
+            dir: root()?.join(profile),
+            mode,
+        })
+    }
+
+    /// A cache that is never read or written.
+    pub fn disabled() -> Self {
+        Self {
+            dir: PathBuf::new(),
+            mode: Mode::Off,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The entry for `key`, if it exists and hasn't expired.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        if self.mode != Mode::Use {
+            return None;
+        }
+        let entry: Entry<T> = self.read(key)?;
+        (now().saturating_sub(entry.fetched_at) < ttl(key).as_secs()).then_some(entry.data)
+    }
+
+    /// Whether a fresh entry for `key` would be served by [`get`](Self::get).
+    pub fn is_fresh(&self, key: &str) -> bool {
+        self.get::<serde::de::IgnoredAny>(key).is_some()
+    }
+
+    /// The entry for `key` however old it is, for when a stale answer beats
+    /// waiting for a fresh one (shell completion).
+    pub fn peek<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        Some(self.read::<T>(key)?.data)
+    }
+
+    /// Store `data` under `key`. Failing to write the cache isn't fatal, so
+    /// errors are ignored.
+    pub fn put<T: Serialize>(&self, key: &str, data: &T) {
+        if self.mode == Mode::Off {
+            return;
+        }
+        let entry = Entry {
+            fetched_at: now(),
+            data,
+        };
+        let path = self.path(key);
+        if let (Some(parent), Ok(contents)) = (path.parent(), serde_json::to_vec(&entry)) {
+            let _ = fs::create_dir_all(parent).and_then(|_| fs::write(&path, contents));
+        }
+    }
+
+    /// Drop the entry for `key`, e.g. after a mutation changed it.
+    pub fn invalidate(&self, key: &str) {
+        if self.mode != Mode::Off {
+            let _ = fs::remove_file(self.path(key));
+        }
+    }
+
+    /// Remove every entry, returning whether there was anything to remove.
+    pub fn clear(&self) -> Result<bool> {
+        match fs::remove_dir_all(&self.dir) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(Error::io(
+                format!("failed to remove {}", self.dir.display()),
+                e,
+            )),
+        }
+    }
+
+    /// Every entry in the cache, sorted by key.
+    pub fn status(&self) -> Vec<EntryStatus> {
+        let mut entries = Vec::new();
+        let mut pending = vec![self.dir.clone()];
+        while let Some(dir) = pending.pop() {
+            let Ok(read) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for path in read.flatten().map(|e| e.path()) {
+                if path.is_dir() {
+                    pending.push(path);
+                    continue;
+                }
+                let Some(key) = path
+                    .strip_prefix(&self.dir)
+                    .ok()
+                    .and_then(|p| p.with_extension("").to_str().map(|k| k.replace('\\', "/")))
+                else {
+                    continue;
+                };
+                let Some(entry) = self.read::<Vec<serde_json::Value>>(&key) else {
+                    continue;
+                };
+                let age = now().saturating_sub(entry.fetched_at);
+                entries.push(EntryStatus {
+                    items: entry.data.len(),
+                    fresh: age < ttl(&key).as_secs(),
+                    age,
+                    key,
+                });
+            }
+        }
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        entries
+    }
+
+    fn read<T: DeserializeOwned>(&self, key: &str) -> Option<Entry<T>> {
+        let contents = fs::read(self.path(key)).ok()?;
+        serde_json::from_slice(&contents).ok()
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+/// The time to live of a key, looked up by its kind (the part before any `/`).
+fn ttl(key: &str) -> Duration {
+    let kind = key.split('/').next().unwrap_or(key);
+    TTLS.iter()
+        .find(|(k, _)| *k == kind)
+        .map_or(Duration::ZERO, |(_, ttl)| *ttl)
+}
@@ -1,11 +1,142 @@
+use crate::auth::{self, Credential};
+use crate::cache::Cache;
+use crate::config::{OAuthSettings, Profile};
 use crate::error::{Error, Result};
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use crate::oauth;
+use crate::output;
+use crate::queries::PageInfo;
+use crate::transport::{self, Request, Response, Transport};
+use cynic_parser::common::OperationType;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::Instrument;
 
 const API_URL: &str = "https://api.linear.app/graphql";
 
+/// Largest page Linear will return for a single connection request.
+const MAX_PAGE_SIZE: u32 = 250;
+
+/// Delay before the first retry; doubled for each further attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Requests or complexity points left untouched when throttling, leaving
+/// headroom for other clients sharing the same key.
+const RESERVE: u64 = 1;
+/// Longest a bulk command will wait for the rate limit to reset.
+const MAX_THROTTLE: Duration = Duration::from_secs(3600);
+
+/// When and how failed requests are retried.
+///
+/// Rate limits (429 or a `RATELIMITED` error), server errors (5xx), timeouts
+/// and connection failures are retried. Mutations are only retried on other
+/// failures than rate limits when `retry_mutations` is set, since a request
+/// that timed out may still have been applied.
+#[derive(Clone, Copy)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub retries: u32,
+    /// Per-request timeout
+    pub timeout: Duration,
+    pub retry_mutations: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            timeout: Duration::from_secs(30),
+            retry_mutations: false,
+        }
+    }
+}
+
+/// Rate-limit budget reported by Linear in the headers of the last response.
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitStatus {
+    pub requests: Option<RateLimitBudget>,
+    pub complexity: Option<RateLimitBudget>,
+    /// Complexity points charged for the last query
+    pub last_complexity: Option<u64>,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitBudget {
+    pub limit: u64,
+    pub remaining: u64,
+    /// When the budget refills, in milliseconds since the Unix epoch
+    pub reset: u64,
+}
+
+impl RateLimitStatus {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+        let budget = |kind: &str| {
+            Some(RateLimitBudget {
+                limit: header(&format!("x-ratelimit-{}-limit", kind))?,
+                remaining: header(&format!("x-ratelimit-{}-remaining", kind))?,
+                reset: header(&format!("x-ratelimit-{}-reset", kind)).unwrap_or_default(),
+            })
+        };
+        Self {
+            requests: budget("requests"),
+            complexity: budget("complexity"),
+            last_complexity: header("x-complexity"),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.requests.is_none() && self.complexity.is_none() && self.last_complexity.is_none()
+    }
+
+    /// How long to wait before sending another request costing about
+    /// `cost` complexity points, if either budget would run out.
+    fn wait_for(&self, cost: u64) -> Option<Duration> {
+        let exhausted = |budget: &Option<RateLimitBudget>, cost: u64| {
+            budget.filter(|b| b.remaining < cost.max(1) + RESERVE)
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        [
+            exhausted(&self.requests, 1),
+            exhausted(&self.complexity, cost),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|b| Duration::from_millis(b.reset.saturating_sub(now)))
+        .max()
+    }
+}
+
 pub struct LinearClient {
-    http: reqwest::Client,
+    transport: Box<dyn Transport>,
+    credential: Mutex<Credential>,
+    /// Where to refresh and persist OAuth tokens, for saved OAuth credentials
+    refresh: Option<TokenRefresh>,
+    retry: RetryPolicy,
+    rate_limit: Mutex<RateLimitStatus>,
+    /// Metadata used for name resolution, kept between runs
+    cache: Cache,
+}
+
+struct TokenRefresh {
+    profile: String,
+    settings: OAuthSettings,
 }
 
 #[derive(Serialize)]
@@ -15,70 +146,589 @@ struct GraphQLRequest<T: Serialize> {
 }
 
 #[derive(Deserialize)]
-struct GraphQLResponse<T> {
-    data: Option<T>,
+struct GraphQLResponse {
+    #[serde(default)]
+    data: Value,
     errors: Option<Vec<GraphQLError>>,
 }
 
+/// One entry of a response's `errors`.
 #[derive(Deserialize)]
 struct GraphQLError {
     message: String,
+    /// Response fields leading to the one that failed, e.g. `["issue"]`
+    #[serde(default)]
+    path: Vec<Value>,
+    #[serde(default)]
+    locations: Vec<Location>,
+    #[serde(default)]
+    extensions: Extensions,
+}
+
+#[derive(Deserialize)]
+struct Location {
+    line: u32,
+    column: u32,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Extensions {
+    /// Linear's error category, e.g. `invalid input`
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    code: Option<String>,
+    /// A message Linear considers fit to show users as is
+    user_presentable_message: Option<String>,
+}
+
+impl GraphQLError {
+    fn is_not_found(&self) -> bool {
+        self.message.starts_with("Entity not found")
+            || self.extensions.code.as_deref() == Some("ENTITY_NOT_FOUND")
+    }
+
+    fn is_rate_limited(&self) -> bool {
+        self.extensions.code.as_deref() == Some("RATELIMITED")
+    }
+
+    fn is_validation(&self) -> bool {
+        self.message == "Argument Validation Error"
+            || self.extensions.kind.as_deref() == Some("invalid input")
+            || matches!(
+                self.extensions.code.as_deref(),
+                Some("INPUT_ERROR" | "GRAPHQL_VALIDATION_FAILED" | "BAD_USER_INPUT")
+            )
+    }
+
+    /// The entity an "Entity not found: Issue" error is about, lowercased.
+    fn entity(&self) -> Option<String> {
+        let entity = self.message.strip_prefix("Entity not found")?;
+        let entity = entity.trim_start_matches(':').trim();
+        (!entity.is_empty()).then(|| entity.to_lowercase())
+    }
+}
+
+impl std::fmt::Display for GraphQLError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let message = self
+            .extensions
+            .user_presentable_message
+            .as_deref()
+            .unwrap_or(&self.message);
+        write!(f, "{}", message)?;
+
+        let mut context = Vec::new();
+        if !self.path.is_empty() {
+            let path: Vec<String> = self
+                .path
+                .iter()
+                .map(|segment| match segment {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect();
+            context.push(format!("at {}", path.join(".")));
+        }
+        if let Some(location) = self.locations.first() {
+            context.push(format!(
+                "line {}, column {}",
+                location.line, location.column
+            ));
+        }
+        if !context.is_empty() {
+            write!(f, " ({})", context.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Turn a response's errors into the most specific [`Error`]: a missing
+/// entity is `NotFound` and rejected input is `Validation`, with a hint
+/// where one helps.
+fn classify(errors: &[GraphQLError]) -> Error {
+    let message = errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    if errors.iter().any(GraphQLError::is_rate_limited) {
+        return Error::RateLimited(60);
+    }
+    if let Some(error) = errors.iter().find(|e| e.is_not_found()) {
+        let not_found = Error::NotFound(message);
+        return match error.entity().as_deref() {
+            Some(entity @ ("issue" | "project" | "document" | "team" | "label")) => not_found
+                .with_hint(format!(
+                    "run `linear {} list` to see available {}s",
+                    entity, entity
+                )),
+            _ => not_found,
+        };
+    }
+    if errors.iter().any(GraphQLError::is_validation) {
+        return Error::Validation(message);
+    }
+    Error::GraphQL(message)
+}
+
+/// The GraphQL endpoint for a profile: `LINEAR_API_URL`, the profile's
+/// `api_url`, or Linear's API.
+fn api_url(profile: &Profile) -> String {
+    std::env::var("LINEAR_API_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+        .or_else(|| profile.api_url.clone())
+        .unwrap_or_else(|| API_URL.to_string())
+}
+
+/// One page of a Relay connection, as selected by a fragment declared with
+/// `queries::connection!`.
+pub struct Connection<T> {
+    pub nodes: Vec<T>,
+    pub page_info: PageInfo,
 }
 
 impl LinearClient {
-    pub fn from_env() -> Result<Self> {
-        let api_key = std::env::var("LINEAR_API_KEY").map_err(|_| Error::MissingApiKey)?;
+    /// Build a client for the given profile, honoring `LINEAR_API_KEY` and
+    /// the profile's API URL override.
+    pub fn from_profile(profile: &Profile) -> Result<Self> {
+        let (credential, source) = match auth::credential(profile) {
+            // A cassette doesn't check credentials, so replaying needs none
+            Err(Error::MissingApiKey) if transport::replaying() => {
+                let credential = Credential::ApiKey {
+                    api_key: String::new(),
+                };
+                return Self::with_credential(profile, credential);
+            }
+            result => result?,
+        };
+        Self::with_source(profile, credential, source)
+    }
 
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            AUTHORIZATION,
-            HeaderValue::from_str(&api_key).expect("invalid api key format"),
-        );
-        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    /// Build a client for the given profile from a credential already looked
+    /// up with [`auth::credential`], refreshing it if it came from the store.
+    pub fn with_source(
+        profile: &Profile,
+        credential: Credential,
+        source: auth::KeySource,
+    ) -> Result<Self> {
+        let mut client = Self::with_credential(profile, credential)?;
+        if source == auth::KeySource::Store {
+            client.refresh = Some(TokenRefresh {
+                profile: profile.name.clone(),
+                settings: profile.oauth.clone(),
+            });
+        }
+        Ok(client)
+    }
+
+    /// Build a client for the given profile's endpoint using an explicit
+    /// credential, which is never refreshed or persisted.
+    ///
+    /// The endpoint is `LINEAR_API_URL`, else the profile's `api_url`, unless
+    /// `--replay` answers requests from a cassette.
+    pub fn with_credential(profile: &Profile, credential: Credential) -> Result<Self> {
+        let transport = transport::for_url(&api_url(profile))?;
+        Ok(Self::new(credential, transport))
+    }
+
+    pub fn new(credential: Credential, transport: Box<dyn Transport>) -> Self {
+        Self {
+            transport,
+            credential: Mutex::new(credential),
+            refresh: None,
+            retry: RetryPolicy::default(),
+            rate_limit: Mutex::new(RateLimitStatus::default()),
+            cache: Cache::disabled(),
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_cache(mut self, cache: Cache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn cache(&self) -> &Cache {
+        &self.cache
+    }
+
+    /// Serve `key` from the metadata cache, awaiting `fetch` and storing its
+    /// result when the entry is missing or expired.
+    pub async fn cached<T>(&self, key: &str, fetch: impl Future<Output = Result<T>>) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        if let Some(data) = self.cache.get(key) {
+            return Ok(data);
+        }
+        let data = fetch.await?;
+        self.cache.put(key, &data);
+        Ok(data)
+    }
 
-        let http = reqwest::Client::builder()
-            .default_headers(headers)
-            .build()
-            .expect("failed to build http client");
+    /// The rate-limit budget as of the last response.
+    pub fn rate_limit(&self) -> RateLimitStatus {
+        self.rate_limit.lock().unwrap().clone()
+    }
 
-        Ok(Self { http })
+    /// Wait for the rate-limit budget to refill if the next request would
+    /// exhaust it, instead of sending it and being rejected with a 429.
+    pub async fn throttle(&self) {
+        let status = self.rate_limit();
+        let Some(delay) = status.wait_for(status.last_complexity.unwrap_or(1)) else {
+            return;
+        };
+        tracing::info!(
+            wait_secs = delay.as_secs_f64().ceil(),
+            "rate limit nearly exhausted; waiting for it to reset"
+        );
+        tokio::time::sleep(delay.min(MAX_THROTTLE)).await;
     }
 
+    /// Send a query or mutation and decode its data. Each call is traced in
+    /// a `graphql` span named after the operation.
     pub async fn query<V, T>(&self, query: &str, variables: V) -> Result<T>
     where
         V: Serialize,
         T: for<'de> Deserialize<'de>,
     {
-        let request = GraphQLRequest {
+        let span = tracing::info_span!("graphql", operation = %operation_name(query));
+        self.execute(query, variables).instrument(span).await
+    }
+
+    async fn execute<V, T>(&self, query: &str, variables: V) -> Result<T>
+    where
+        V: Serialize,
+        T: for<'de> Deserialize<'de>,
+    {
+        let body = serde_json::to_value(GraphQLRequest {
             query: query.to_string(),
             variables,
-        };
+        })
+        .map_err(|e| Error::GraphQL(format!("failed to encode variables: {}", e)))?;
+        tracing::debug!(variables = %body["variables"], "request");
 
-        let response = self.http.post(API_URL).json(&request).send().await?;
+        let response = self.send_with_retry(body).await?;
 
-        let status = response.status();
-        if status == reqwest::StatusCode::UNAUTHORIZED {
+        let status = response.status;
+        if status == StatusCode::UNAUTHORIZED {
             return Err(Error::Unauthorized);
         }
-        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(60);
-            return Err(Error::RateLimited(retry_after));
+        if is_rate_limited(&response) {
+            return Err(Error::RateLimited(retry_after(&response).unwrap_or(60)));
+        }
+        if status.is_server_error() {
+            return Err(Error::GraphQL(format!("server error: {}", status)));
+        }
+
+        let gql_response: GraphQLResponse = serde_json::from_slice(&response.body)
+            .map_err(|e| Error::GraphQL(format!("invalid response: {}", e)))?;
+        let errors = gql_response.errors.unwrap_or_default();
+
+        if gql_response.data.is_null() {
+            if errors.is_empty() {
+                return Err(Error::GraphQL("no data in response".to_string()));
+            }
+            return Err(classify(&errors));
+        }
+        match serde_json::from_value(gql_response.data) {
+            // Partial data: show what was fetched and warn about the rest
+            Ok(data) => {
+                for error in &errors {
+                    output::print_warning(&error.to_string());
+                }
+                Ok(data)
+            }
+            // A field that failed was required, so the error explains more
+            // than the decoding failure
+            Err(_) if !errors.is_empty() => Err(classify(&errors)),
+            Err(e) => Err(Error::GraphQL(format!("invalid response: {}", e))),
+        }
+    }
+
+    /// Run a typed operation built with cynic.
+    pub async fn run<T, V>(&self, operation: cynic::Operation<T, V>) -> Result<T>
+    where
+        V: Serialize,
+        T: for<'de> Deserialize<'de>,
+    {
+        self.query(&operation.query, operation.variables).await
+    }
+
+    /// Send a request, retrying transient failures according to the retry
+    /// policy. The last response or error is returned once retries run out.
+    async fn send_with_retry(&self, body: Value) -> Result<Response> {
+        let mut request = Request {
+            authorization: String::new(),
+            timeout: self.retry.timeout,
+            body,
+        };
+        let retryable = self.retry.retry_mutations || !is_mutation(request.query());
+        let mut attempt = 1;
+
+        loop {
+            let result = self.send_authorized(&mut request).await;
+            // A rate-limited request was turned away before running, so even
+            // a mutation is safe to send again
+            let (delay, reason, safe) = match &result {
+                Ok(r) if is_rate_limited(r) => (
+                    retry_after(r).map_or_else(|| backoff(attempt), Duration::from_secs),
+                    "rate limited".to_string(),
+                    true,
+                ),
+                Ok(r) if r.status.is_server_error() => {
+                    (backoff(attempt), r.status.to_string(), false)
+                }
+                Err(Error::Network(e)) if e.is_connect() || e.is_timeout() || e.is_request() => {
+                    (backoff(attempt), e.to_string(), false)
+                }
+                _ => return result,
+            };
+
+            if !(retryable || safe) || attempt > self.retry.retries {
+                return result;
+            }
+            tracing::info!(
+                %reason,
+                delay_secs = delay.as_secs_f64(),
+                attempt = attempt + 1,
+                attempts = self.retry.retries + 1,
+                "retrying"
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Send a request once, refreshing an expired OAuth token if needed.
+    async fn send_authorized(&self, request: &mut Request) -> Result<Response> {
+        let mut response = self.send(request).await?;
+        if response.status == StatusCode::UNAUTHORIZED && self.refresh_token().await? {
+            response = self.send(request).await?;
         }
+        Ok(response)
+    }
 
-        let gql_response: GraphQLResponse<T> = response.json().await?;
+    async fn send(&self, request: &mut Request) -> Result<Response> {
+        request.authorization = self.credential.lock().unwrap().authorization();
+        let started = Instant::now();
+        let result = self.transport.send(request).await;
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        let response = result.inspect_err(|e| {
+            tracing::info!(elapsed_ms, error = %e, "request failed");
+        })?;
+        tracing::info!(
+            status = response.status.as_u16(),
+            elapsed_ms,
+            bytes = response.body.len(),
+            "response"
+        );
 
-        if let Some(errors) = gql_response.errors {
-            let messages: Vec<_> = errors.iter().map(|e| e.message.as_str()).collect();
-            return Err(Error::GraphQL(messages.join(", ")));
+        let status = RateLimitStatus::from_headers(&response.headers);
+        if !status.is_empty() {
+            tracing::debug!(
+                requests = %Budget(status.requests),
+                complexity = %Budget(status.complexity),
+                cost = status.last_complexity.unwrap_or_default(),
+                "rate limit"
+            );
+            *self.rate_limit.lock().unwrap() = status;
         }
+        Ok(response)
+    }
 
-        gql_response
-            .data
-            .ok_or_else(|| Error::GraphQL("no data in response".to_string()))
+    /// Swap an expired OAuth access token for a new one and save it. Returns
+    /// whether the request is worth retrying.
+    async fn refresh_token(&self) -> Result<bool> {
+        let Some(refresh) = &self.refresh else {
+            return Ok(false);
+        };
+        let refresh_token = match &*self.credential.lock().unwrap() {
+            Credential::OAuth(token) => token.refresh_token.clone(),
+            Credential::ApiKey { .. } => None,
+        };
+        let Some(refresh_token) = refresh_token else {
+            return Ok(false);
+        };
+
+        let token = oauth::refresh(&refresh.settings, &refresh_token).await?;
+        let credential = Credential::OAuth(token);
+        auth::store()?.save(&refresh.profile, &credential)?;
+        *self.credential.lock().unwrap() = credential;
+        Ok(true)
     }
+
+    /// Fetch every node of a connection query, following `pageInfo` cursors
+    /// until `limit` nodes have been collected (or all of them when `None`).
+    pub async fn paginate<Q, V, T>(
+        &self,
+        limit: Option<u32>,
+        build: impl Fn(i32, Option<String>) -> cynic::Operation<Q, V>,
+        extract: impl Fn(Q) -> Connection<T>,
+    ) -> Result<Vec<T>>
+    where
+        Q: for<'de> Deserialize<'de>,
+        V: Serialize,
+    {
+        let mut nodes = Vec::new();
+        self.paginate_each(limit, build, extract, |page| nodes.extend(page))
+            .await?;
+        Ok(nodes)
+    }
+
+    /// Fetch a connection like [`paginate`](Self::paginate) and hand the
+    /// nodes to `print`. When lists are streamed (`--format ndjson`), each
+    /// page is written as soon as it arrives instead, so long `--all`
+    /// listings start printing right away.
+    pub async fn paginate_print<Q, V, T>(
+        &self,
+        limit: Option<u32>,
+        build: impl Fn(i32, Option<String>) -> cynic::Operation<Q, V>,
+        extract: impl Fn(Q) -> Connection<T>,
+        print: impl FnOnce(&[T]),
+    ) -> Result<()>
+    where
+        Q: for<'de> Deserialize<'de>,
+        V: Serialize,
+        T: Serialize,
+    {
+        if output::streams_lists() {
+            return self
+                .paginate_each(limit, build, extract, |page| output::print_page(&page))
+                .await;
+        }
+        let nodes = self.paginate(limit, build, extract).await?;
+        print(&nodes);
+        Ok(())
+    }
+
+    /// Like [`paginate`](Self::paginate), but hands each page to `on_page` as
+    /// soon as it arrives instead of collecting the whole result.
+    ///
+    /// `build` constructs the operation for a page size and cursor, which the
+    /// query must pass to the connection returned by `extract`.
+    pub async fn paginate_each<Q, V, T>(
+        &self,
+        limit: Option<u32>,
+        build: impl Fn(i32, Option<String>) -> cynic::Operation<Q, V>,
+        extract: impl Fn(Q) -> Connection<T>,
+        mut on_page: impl FnMut(Vec<T>),
+    ) -> Result<()>
+    where
+        Q: for<'de> Deserialize<'de>,
+        V: Serialize,
+    {
+        let mut remaining = limit;
+        let mut after: Option<String> = None;
+
+        loop {
+            let first = remaining.map_or(MAX_PAGE_SIZE, |r| r.min(MAX_PAGE_SIZE));
+            if first == 0 {
+                return Ok(());
+            }
+            if after.is_some() {
+                self.throttle().await;
+            }
+
+            let response = self.run(build(first as i32, after.take())).await?;
+            let mut connection = extract(response);
+
+            if let Some(r) = remaining.as_mut() {
+                connection.nodes.truncate(*r as usize);
+                *r -= connection.nodes.len() as u32;
+            }
+            let fetched = connection.nodes.len();
+            on_page(connection.nodes);
+
+            match connection.page_info.end_cursor {
+                Some(cursor) if connection.page_info.has_next_page && fetched > 0 => {
+                    after = Some(cursor);
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Whether Linear turned a request away for exceeding a rate limit: a 429,
+/// or a `RATELIMITED` GraphQL error, which usually comes with a 400.
+fn is_rate_limited(response: &Response) -> bool {
+    if response.status == StatusCode::TOO_MANY_REQUESTS {
+        return true;
+    }
+    !response.status.is_success()
+        && serde_json::from_slice::<GraphQLResponse>(&response.body).is_ok_and(|r| {
+            r.errors
+                .unwrap_or_default()
+                .iter()
+                .any(GraphQLError::is_rate_limited)
+        })
+}
+
+fn retry_after(response: &Response) -> Option<u64> {
+    response
+        .headers
+        .get("retry-after")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+}
+
+/// Exponential backoff for the given attempt, with up to 50% random jitter
+/// so concurrent clients don't retry in lockstep.
+fn backoff(attempt: u32) -> Duration {
+    let delay = INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt - 1))
+        .min(MAX_BACKOFF);
+
+    let mut random = [0u8; 4];
+    let fraction = match getrandom::getrandom(&mut random) {
+        Ok(()) => f64::from(u32::from_le_bytes(random)) / f64::from(u32::MAX),
+        Err(_) => 0.5,
+    };
+    delay.mul_f64(1.0 - fraction * 0.5)
+}
+
+/// Shows a rate-limit budget in logs as `remaining/limit`.
+struct Budget(Option<RateLimitBudget>);
+
+impl std::fmt::Display for Budget {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.0 {
+            Some(budget) => write!(f, "{}/{}", budget.remaining, budget.limit),
+            None => f.write_str("-"),
+        }
+    }
+}
+
+/// The type and name of the operation in a GraphQL document, read from the
+/// parsed document since comments and fragment definitions may come before
+/// it. `None` when the document doesn't parse or has no operation.
+fn operation(query: &str) -> Option<(OperationType, Option<String>)> {
+    let document = cynic_parser::parse_executable_document(query).ok()?;
+    let operation = document.operations().next()?;
+    Some((
+        operation.operation_type(),
+        operation.name().map(str::to_string),
+    ))
+}
+
+/// Whether a document may write. Anything that isn't recognizably a query
+/// counts, so it is never retried by mistake.
+fn is_mutation(query: &str) -> bool {
+    !matches!(operation(query), Some((OperationType::Query, _)))
+}
+
+/// The operation name of a GraphQL document, e.g. `Issues` for
+/// `query Issues($first: Int) { ... }`.
+pub fn operation_name(query: &str) -> String {
+    operation(query)
+        .and_then(|(_, name)| name)
+        .unwrap_or_else(|| "anonymous operation".to_string())
 }
@@ -0,0 +1,270 @@
+use clap::{Args, Subcommand};
+use cynic::QueryBuilder;
+use serde_json::{Map, Value};
+
+use crate::client::LinearClient;
+use crate::error::{Error, Result};
+use crate::input;
+use crate::output;
+use crate::queries::user::ViewerIdQuery;
+use crate::validate;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub struct ApiArgs {
+    #[command(subcommand)]
+    pub command: Option<ApiCommands>,
+
+    /// GraphQL query or mutation, or @file to read it from a file (@- for stdin)
+    #[arg(required = true)]
+    pub query: Option<String>,
+
+    /// Set a string variable (repeatable)
+    #[arg(short = 'f', long = "raw-field", value_name = "NAME=VALUE")]
+    pub raw_fields: Vec<String>,
+
+    /// Set a variable to a JSON value, or @file to read the JSON from a file
+    #[arg(short = 'F', long = "field", value_name = "NAME=JSON")]
+    pub fields: Vec<String>,
+
+    /// Follow the connection at this path (e.g. `issues` or `team.issues`)
+    /// through every page; the query must take an `$after: String` cursor
+    /// and select `pageInfo { hasNextPage endCursor }`
+    #[arg(long, value_name = "PATH")]
+    pub paginate: Option<String>,
+
+    /// Filter the result with a jq expression
+    #[arg(long, value_name = "EXPR")]
+    pub jq: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum ApiCommands {
+    /// Show the remaining request and complexity budget
+    Limits,
+}
+
+pub async fn handle_limits(client: &LinearClient) -> Result<()> {
+    // The cheapest query available; only its response headers are of interest
+    client.run(ViewerIdQuery::build(())).await?;
+    output::print_rate_limit(&client.rate_limit());
+    Ok(())
+}
+
+pub async fn handle_query(client: &LinearClient, args: &ApiArgs) -> Result<()> {
+    let query = read_query(args.query.as_deref().unwrap_or_default())?;
+    let document = validate::document(&query)?;
+    let variables = variables(args)?;
+
+    // Compile the filter up front so a typo doesn't cost a request
+    let filter = args.jq.as_deref().map(Filter::compile).transpose()?;
+
+    let data = match &args.paginate {
+        Some(path) => {
+            let declares_cursor = document
+                .operations()
+                .flat_map(|o| o.variable_definitions())
+                .any(|v| v.name() == "after");
+            if !declares_cursor {
+                return Err(Error::InvalidInput(
+                    "--paginate needs the query to declare an `$after: String` variable"
+                        .to_string(),
+                ));
+            }
+            paginate(client, &query, variables, path).await?
+        }
+        None => client.query(&query, Value::Object(variables)).await?,
+    };
+
+    match filter {
+        Some(filter) => {
+            for value in filter.run(data)? {
+                match value {
+                    Value::String(s) => println!("{}", s),
+                    other => println!("{}", pretty(&other)),
+                }
+            }
+        }
+        None => println!("{}", pretty(&data)),
+    }
+    Ok(())
+}
+
+/// Run the query page by page, concatenating the `nodes` of the connection
+/// at `path` into the first response.
+async fn paginate(
+    client: &LinearClient,
+    query: &str,
+    mut variables: Map<String, Value>,
+    path: &str,
+) -> Result<Value> {
+    let mut result: Option<Value> = None;
+
+    loop {
+        let mut page: Value = client
+            .query(query, Value::Object(variables.clone()))
+            .await?;
+        let connection = connection(&mut page, path)?;
+        let nodes = match connection.get_mut("nodes").map(Value::take) {
+            Some(Value::Array(nodes)) => nodes,
+            _ => {
+                return Err(Error::InvalidInput(format!(
+                    "--paginate: select `nodes` on the connection at {}",
+                    path
+                )))
+            }
+        };
+        let page_info = connection.get("pageInfo").cloned().unwrap_or_default();
+        let cursor = match (
+            page_info.get("hasNextPage").and_then(Value::as_bool),
+            page_info.get("endCursor").and_then(Value::as_str),
+        ) {
+            (Some(true), Some(cursor)) if !nodes.is_empty() => Some(cursor.to_string()),
+            (Some(_), _) => None,
+            _ => {
+                return Err(Error::InvalidInput(format!(
+                "--paginate: select `pageInfo {{ hasNextPage endCursor }}` on the connection at {}",
+                path
+            )))
+            }
+        };
+
+        let merged = result.get_or_insert_with(|| page.clone());
+        let target = connection_mut(merged, path);
+        if let Some(Value::Array(all)) = target.get_mut("nodes") {
+            all.extend(nodes);
+        } else {
+            target["nodes"] = Value::Array(nodes);
+        }
+        target["pageInfo"] = page_info;
+
+        match cursor {
+            Some(cursor) => {
+                variables.insert("after".to_string(), Value::String(cursor));
+                client.throttle().await;
+            }
+            None => return Ok(result.unwrap_or_default()),
+        }
+    }
+}
+
+/// The connection object at a dot-separated path in the response data.
+fn connection<'a>(data: &'a mut Value, path: &str) -> Result<&'a mut Map<String, Value>> {
+    let mut value = data;
+    for key in path.split('.') {
+        value = value.get_mut(key).ok_or_else(|| {
+            Error::InvalidInput(format!("--paginate: no field {} in the result", path))
+        })?;
+    }
+    value
+        .as_object_mut()
+        .ok_or_else(|| Error::InvalidInput(format!("--paginate: {} is not a connection", path)))
+}
+
+fn connection_mut<'a>(data: &'a mut Value, path: &str) -> &'a mut Value {
+    path.split('.').fold(data, |value, key| &mut value[key])
+}
+
+fn read_query(query: &str) -> Result<String> {
+    match query.strip_prefix('@') {
+        Some(path) => input::read_file(path),
+        None => Ok(query.to_string()),
+    }
+}
+
+/// Collect the `-f` and `-F` flags into the request's variables.
+fn variables(args: &ApiArgs) -> Result<Map<String, Value>> {
+    let mut variables = Map::new();
+    for field in &args.raw_fields {
+        let (name, value) = split_field(field)?;
+        variables.insert(name.to_string(), Value::String(value.to_string()));
+    }
+    for field in &args.fields {
+        let (name, value) = split_field(field)?;
+        let json = match value.strip_prefix('@') {
+            Some(path) => input::read_file(path)?,
+            None => value.to_string(),
+        };
+        let value = serde_json::from_str(&json).map_err(|e| {
+            Error::InvalidInput(format!("invalid JSON for variable {}: {}", name, e))
+        })?;
+        variables.insert(name.to_string(), value);
+    }
+    Ok(variables)
+}
+
+fn split_field(field: &str) -> Result<(&str, &str)> {
+    field
+        .split_once('=')
+        .filter(|(name, _)| !name.is_empty())
+        .ok_or_else(|| Error::InvalidInput(format!("expected NAME=VALUE, got '{}'", field)))
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+/// A compiled `--jq` expression.
+struct Filter(jaq_core::Filter<jaq_core::Native<jaq_json::Val>>);
+
+impl Filter {
+    fn compile(code: &str) -> Result<Self> {
+        use jaq_core::load::{Arena, File, Loader};
+
+        let invalid =
+            |message: String| Error::InvalidInput(format!("invalid --jq expression: {}", message));
+
+        let loader = Loader::new(jaq_std::defs().chain(jaq_json::defs()));
+        let arena = Arena::default();
+        let modules = loader
+            .load(&arena, File { code, path: () })
+            .map_err(|errors| {
+                let messages: Vec<String> = errors
+                    .into_iter()
+                    .flat_map(|(_, error)| match error {
+                        jaq_core::load::Error::Io(errors) => {
+                            errors.into_iter().map(|(_, e)| e).collect()
+                        }
+                        jaq_core::load::Error::Lex(errors) => errors
+                            .into_iter()
+                            .map(|(expected, _)| format!("expected {}", expected.as_str()))
+                            .collect(),
+                        jaq_core::load::Error::Parse(errors) => errors
+                            .into_iter()
+                            .map(|(expected, found)| {
+                                format!("expected {} before '{}'", expected.as_str(), found)
+                            })
+                            .collect::<Vec<_>>(),
+                    })
+                    .collect();
+                invalid(messages.join(", "))
+            })?;
+
+        let filter = jaq_core::Compiler::default()
+            .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+            .compile(modules)
+            .map_err(|errors| {
+                let messages: Vec<String> = errors
+                    .into_iter()
+                    .flat_map(|(_, errors)| errors)
+                    .map(|(name, undefined)| format!("undefined {} {}", undefined.as_str(), name))
+                    .collect();
+                invalid(messages.join(", "))
+            })?;
+        Ok(Filter(filter))
+    }
+
+    fn run(&self, input: Value) -> Result<Vec<Value>> {
+        use jaq_core::{Ctx, RcIter};
+
+        let inputs = RcIter::new(core::iter::empty());
+        self.0
+            .run((Ctx::new([], &inputs), jaq_json::Val::from(input)))
+            .map(|result| {
+                result
+                    .map(Value::from)
+                    .map_err(|e| Error::InvalidInput(format!("--jq: {}", e)))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,138 @@
+use std::io::{BufRead, IsTerminal};
+
+use clap::{Args, Subcommand};
+use serde::Serialize;
+
+use crate::auth::{self, Credential, KeySource};
+use crate::client::LinearClient;
+use crate::commands::user;
+use crate::config::Profile;
+use crate::error::{Error, Result};
+use crate::oauth;
+use crate::output;
+use crate::queries::user::User;
+
+#[derive(Subcommand)]
+pub enum AuthCommands {
+    /// Save an API key or OAuth token for the active profile
+    Login(LoginArgs),
+    /// Remove the saved credential for the active profile
+    Logout,
+    /// Show the active credential and who it belongs to
+    Status,
+}
+
+#[derive(Args)]
+pub struct LoginArgs {
+    /// Read the API key from stdin instead of prompting
+    #[arg(long, conflicts_with = "oauth")]
+    pub with_key: bool,
+
+    /// Log in through the browser with the profile's OAuth application
+    /// instead of an API key
+    #[arg(long)]
+    pub oauth: bool,
+}
+
+#[derive(Serialize)]
+pub struct AuthStatus {
+    pub profile: String,
+    pub source: String,
+    pub credential: String,
+    pub user: User,
+}
+
+pub async fn handle_login(profile: &Profile, args: &LoginArgs) -> Result<()> {
+    let credential = if args.oauth {
+        Credential::OAuth(oauth::authorize(&profile.oauth).await?)
+    } else {
+        Credential::ApiKey {
+            api_key: read_key(args.with_key)?,
+        }
+    };
+
+    // Validate the credential before saving it
+    let client = LinearClient::with_credential(profile, credential.clone())?;
+    let viewer = user::fetch_viewer(&client).await?;
+
+    let store = auth::store()?;
+    store.save(&profile.name, &credential)?;
+
+    if let Some(source) = auth::overriding_source(profile) {
+        output::print_warning(&format!(
+            "the {} takes precedence over the saved credential",
+            source.describe()
+        ));
+    }
+
+    output::print_auth_status(&AuthStatus {
+        profile: profile.name.clone(),
+        source: describe_source(KeySource::Store)?,
+        credential: credential.describe(),
+        user: viewer,
+    });
+    Ok(())
+}
+
+pub fn handle_logout(profile: &Profile) -> Result<()> {
+    let store = auth::store()?;
+    if store.delete(&profile.name)? {
+        println!("Removed saved credential for profile '{}'.", profile.name);
+    } else {
+        println!("No saved credential for profile '{}'.", profile.name);
+    }
+
+    if std::env::var_os("LINEAR_API_KEY").is_some() {
+        output::print_warning("LINEAR_API_KEY is still set in the environment");
+    }
+    Ok(())
+}
+
+pub async fn handle_status(profile: &Profile) -> Result<()> {
+    let (credential, source) = auth::credential(profile)?;
+    let client = LinearClient::with_source(profile, credential.clone(), source)?;
+    let viewer = user::fetch_viewer(&client).await?;
+
+    output::print_auth_status(&AuthStatus {
+        profile: profile.name.clone(),
+        source: describe_source(source)?,
+        credential: credential.describe(),
+        user: viewer,
+    });
+    Ok(())
+}
+
+fn describe_source(source: KeySource) -> Result<String> {
+    if source == KeySource::Store {
+        Ok(format!(
+            "{} ({})",
+            source.describe(),
+            auth::store()?.describe()
+        ))
+    } else {
+        Ok(source.describe().to_string())
+    }
+}
+
+/// Prompt for the key without echoing it, or read it from stdin when piped.
+fn read_key(from_stdin: bool) -> Result<String> {
+    let stdin = std::io::stdin();
+    let key = if from_stdin || !stdin.is_terminal() {
+        let mut line = String::new();
+        stdin
+            .lock()
+            .read_line(&mut line)
+            .map_err(|e| Error::io("failed to read API key", e))?;
+        line
+    } else {
+        eprintln!("Create a personal API key at https://linear.app/settings/api");
+        rpassword::prompt_password("Paste your API key: ")
+            .map_err(|e| Error::io("failed to read API key", e))?
+    };
+
+    let key = key.trim().to_string();
+    if key.is_empty() {
+        return Err(Error::MissingApiKey);
+    }
+    Ok(key)
+}
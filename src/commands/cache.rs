@@ -0,0 +1,67 @@
+use clap::{Args, Subcommand};
+
+use crate::cache::{self, Cache, Mode};
+use crate::error::{Error, Result};
+use crate::output;
+
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    /// Show what is cached for the active profile and how old it is
+    Status,
+    /// Remove cached data for the active profile
+    Clear(ClearCacheArgs),
+}
+
+#[derive(Args)]
+pub struct ClearCacheArgs {
+    /// Clear the cache of every profile
+    #[arg(long)]
+    pub all: bool,
+}
+
+pub fn handle_status(profile: &str) -> Result<()> {
+    let cache = Cache::new(profile, Mode::Use)?;
+    output::print_cache_status(cache.dir(), &cache.status());
+    Ok(())
+}
+
+pub fn handle_clear(profile: &str, args: &ClearCacheArgs) -> Result<()> {
+    if args.all {
+        if clear_profiles()? {
+            println!("Cleared the cache of every profile");
+        } else {
+            println!("The cache is empty");
+        }
+        return Ok(());
+    }
+
+    if Cache::new(profile, Mode::Use)?.clear()? {
+        println!("Cleared the cache of profile '{}'", profile);
+    } else {
+        println!("The cache of profile '{}' is empty", profile);
+    }
+    Ok(())
+}
+
+/// Remove every profile's cache directory, returning whether there were
+/// any. Files in the cache root, such as the update notifier's state, stay.
+fn clear_profiles() -> Result<bool> {
+    let root = cache::root()?;
+    let entries = match std::fs::read_dir(&root) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(Error::io(format!("failed to read {}", root.display()), e)),
+    };
+    let mut cleared = false;
+    for entry in entries {
+        let path = entry
+            .map_err(|e| Error::io(format!("failed to read {}", root.display()), e))?
+            .path();
+        if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+                .map_err(|e| Error::io(format!("failed to remove {}", path.display()), e))?;
+            cleared = true;
+        }
+    }
+    Ok(cleared)
+}
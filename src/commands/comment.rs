@@ -1,9 +1,18 @@
 use clap::{Args, Subcommand};
-use serde::Deserialize;
+use clap_complete::ArgValueCandidates;
+use cynic::{MutationBuilder, QueryBuilder};
 
 use crate::client::LinearClient;
+use crate::complete;
 use crate::error::Result;
+use crate::input::{self, Draft};
 use crate::output;
+use crate::queries::comment::{
+    CommentCreateInput, CreateCommentMutation, CreateCommentVariables, IssueCommentsQuery,
+};
+use crate::queries::IdPageVariables;
+use crate::resolve;
+use crate::store::Store;
 
 #[derive(Subcommand)]
 pub enum CommentCommands {
@@ -16,103 +25,73 @@ pub enum CommentCommands {
 #[derive(Args)]
 pub struct ListCommentArgs {
     /// Issue ID (e.g., ENG-123)
+    #[arg(add = ArgValueCandidates::new(complete::issues))]
     pub issue: String,
 }
 
 #[derive(Args)]
 pub struct CreateCommentArgs {
     /// Issue ID (e.g., ENG-123)
-    #[arg(long)]
+    #[arg(long, add = ArgValueCandidates::new(complete::issues))]
     pub issue: String,
 
-    /// Comment body (markdown supported)
+    /// Comment body (markdown supported) [required unless written in the editor]
     #[arg(long)]
-    pub body: String,
-}
-
-#[derive(Deserialize)]
-struct IssueCommentsResponse {
-    issue: IssueWithComments,
-}
-
-#[derive(Deserialize)]
-struct IssueWithComments {
-    comments: CommentsConnection,
-}
+    pub body: Option<String>,
 
-#[derive(Deserialize)]
-struct CommentsConnection {
-    nodes: Vec<Comment>,
-}
-
-#[derive(Deserialize, Clone)]
-pub struct Comment {
-    #[allow(dead_code)]
-    pub id: String,
-    pub body: String,
-    #[serde(rename = "createdAt")]
-    pub created_at: String,
-    pub user: Option<CommentUser>,
-}
-
-#[derive(Deserialize, Clone)]
-pub struct CommentUser {
-    pub name: String,
-}
+    /// Read the body from a file ("-" for stdin)
+    #[arg(long, value_name = "FILE", conflicts_with = "body")]
+    pub body_file: Option<String>,
 
-#[derive(Deserialize)]
-struct CreateCommentResponse {
-    #[serde(rename = "commentCreate")]
-    comment_create: CommentCreatePayload,
+    /// Write the body in $VISUAL or $EDITOR [default: when no body is given
+    /// in a terminal]
+    #[arg(short, long)]
+    pub editor: bool,
 }
 
-#[derive(Deserialize)]
-struct CommentCreatePayload {
-    success: bool,
+pub async fn handle_list(client: &LinearClient, args: &ListCommentArgs) -> Result<()> {
+    client
+        .paginate_print(
+            None,
+            |first, after| {
+                IssueCommentsQuery::build(IdPageVariables {
+                    id: args.issue.clone(),
+                    first,
+                    after,
+                })
+            },
+            |r| r.issue.comments.into(),
+            output::print_comments,
+        )
+        .await
 }
 
-const ISSUE_COMMENTS_QUERY: &str = r#"
-    query IssueComments($id: String!) {
-        issue(id: $id) {
-            comments {
-                nodes {
-                    id
-                    body
-                    createdAt
-                    user {
-                        name
-                    }
-                }
-            }
-        }
-    }
-"#;
-
-const CREATE_COMMENT_MUTATION: &str = r#"
-    mutation CreateComment($input: CommentCreateInput!) {
-        commentCreate(input: $input) {
-            success
-        }
-    }
-"#;
-
-pub async fn handle_list(client: &LinearClient, args: &ListCommentArgs) -> Result<()> {
-    let variables = serde_json::json!({ "id": args.issue });
-    let response: IssueCommentsResponse = client.query(ISSUE_COMMENTS_QUERY, variables).await?;
-    output::print_comments(&response.issue.comments.nodes);
+pub fn handle_list_offline(store: &Store, args: &ListCommentArgs) -> Result<()> {
+    output::print_comments(&store.comments(&args.issue)?);
     Ok(())
 }
 
 pub async fn handle_create(client: &LinearClient, args: &CreateCommentArgs) -> Result<()> {
-    let input = serde_json::json!({
-        "issueId": args.issue,
-        "body": args.body
+    let body = input::text(args.body.as_deref(), args.body_file.as_deref())?;
+    let body = if input::wants_editor(args.editor, body.is_none()) {
+        input::edit("comment", &Draft::new(body.as_deref()))?.body
+    } else {
+        body.ok_or_else(|| input::missing("--body"))?
+    };
+
+    let issue = resolve::issue(client, &args.issue).await?;
+    let operation = CreateCommentMutation::build(CreateCommentVariables {
+        input: CommentCreateInput {
+            issue_id: issue.id.into_inner(),
+            body,
+        },
     });
-    let variables = serde_json::json!({ "input": input });
-    let response: CreateCommentResponse = client.query(CREATE_COMMENT_MUTATION, variables).await?;
+    let response = client.run(operation).await?;
 
     if response.comment_create.success {
-        println!("Comment added.");
+        if let Some(comment) = response.comment_create.comment {
+            output::print_created_comment(&comment);
+        }
     } else {
         return Err(crate::error::Error::GraphQL(
             "Failed to create comment".to_string(),
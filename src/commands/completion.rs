@@ -0,0 +1,30 @@
+use std::io::Write;
+
+use clap::{Args, CommandFactory};
+use clap_complete::env::Shells;
+use clap_complete::Shell;
+
+use crate::error::{Error, Result};
+
+#[derive(Args)]
+pub struct CompletionArgs {
+    /// Shell to print the completion script for
+    pub shell: Shell,
+}
+
+/// Print the script that registers `linear`'s completions with a shell.
+/// The script calls back into the binary it was printed by.
+pub fn handle_completion(args: &CompletionArgs) -> Result<()> {
+    let name = crate::Cli::command().get_name().to_string();
+    let completer = std::env::args().next().unwrap_or_else(|| name.clone());
+    let shells = Shells::builtins();
+    let shell = shells
+        .completer(&args.shell.to_string())
+        .ok_or_else(|| Error::InvalidInput(format!("unsupported shell: {}", args.shell)))?;
+
+    let mut script = Vec::new();
+    shell
+        .write_registration("COMPLETE", &name, &name, &completer, &mut script)
+        .and_then(|_| std::io::stdout().write_all(&script))
+        .map_err(|e| Error::io("failed to write the completion script", e))
+}
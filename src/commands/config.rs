@@ -0,0 +1,141 @@
+use clap::{Args, Subcommand};
+use serde::Serialize;
+
+use crate::auth;
+use crate::config::{self, Config};
+use crate::error::Result;
+use crate::output;
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Print a config value from the active profile
+    Get(GetConfigArgs),
+    /// Set a config value on the active profile
+    Set(SetConfigArgs),
+    /// Remove a config value from the active profile
+    Unset(GetConfigArgs),
+    /// List the active profile's config values
+    List,
+    /// List configured profiles
+    Profiles,
+}
+
+#[derive(Args)]
+pub struct GetConfigArgs {
+    /// Config key (e.g., default_team, issue_list.state)
+    #[arg(value_parser = clap::builder::PossibleValuesParser::new(config::KEYS))]
+    pub key: String,
+}
+
+#[derive(Args)]
+pub struct SetConfigArgs {
+    /// Config key (e.g., default_team, issue_list.state)
+    #[arg(value_parser = clap::builder::PossibleValuesParser::new(config::KEYS))]
+    pub key: String,
+
+    /// New value
+    pub value: String,
+
+    /// Also make this profile the default
+    #[arg(long)]
+    pub make_default: bool,
+}
+
+#[derive(Serialize)]
+pub struct ConfigEntry {
+    pub key: String,
+    pub value: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ProfileEntry {
+    pub name: String,
+    pub active: bool,
+}
+
+pub fn handle_get(profile_name: Option<&str>, args: &GetConfigArgs) -> Result<()> {
+    let config = Config::load()?;
+    let profile = config.profile(&config.active_profile_name(profile_name))?;
+    if let Some(value) = profile.get(&args.key)? {
+        println!("{}", value);
+    }
+    Ok(())
+}
+
+pub fn handle_set(profile_name: Option<&str>, args: &SetConfigArgs) -> Result<()> {
+    update_profile(
+        profile_name,
+        &args.key,
+        Some(args.value.clone()),
+        args.make_default,
+    )
+}
+
+pub fn handle_unset(profile_name: Option<&str>, args: &GetConfigArgs) -> Result<()> {
+    update_profile(profile_name, &args.key, None, false)
+}
+
+fn update_profile(
+    profile_name: Option<&str>,
+    key: &str,
+    value: Option<String>,
+    make_default: bool,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let name = config.active_profile_name(profile_name);
+
+    let profile = config.profiles.entry(name.clone()).or_default();
+    profile.set(key, value)?;
+    if make_default {
+        config.default_profile = Some(name);
+    }
+
+    config.save()
+}
+
+pub fn handle_list(profile_name: Option<&str>) -> Result<()> {
+    let config = Config::load()?;
+    let profile = config.profile(&config.active_profile_name(profile_name))?;
+
+    let entries = config::KEYS
+        .iter()
+        .map(|key| {
+            let value = profile.get(key)?.map(|v| {
+                if config::SECRET_KEYS.contains(key) {
+                    auth::mask(&v)
+                } else {
+                    v
+                }
+            });
+            Ok(ConfigEntry {
+                key: key.to_string(),
+                value,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    output::print_config(&entries);
+    Ok(())
+}
+
+pub fn handle_profiles(profile_name: Option<&str>) -> Result<()> {
+    let config = Config::load()?;
+    let active = config.active_profile_name(profile_name);
+
+    let mut names: Vec<&String> = config.profiles.keys().collect();
+    if !names.contains(&&active) {
+        names.push(&active);
+        names.sort();
+    }
+
+    let entries: Vec<ProfileEntry> = names
+        .into_iter()
+        .map(|name| ProfileEntry {
+            active: *name == active,
+            name: name.clone(),
+        })
+        .collect();
+
+    output::print_profiles(&entries);
+    Ok(())
+}
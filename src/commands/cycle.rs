@@ -1,9 +1,14 @@
 use clap::{Args, Subcommand};
-use serde::Deserialize;
+use clap_complete::ArgValueCandidates;
+use cynic::QueryBuilder;
 
 use crate::client::LinearClient;
+use crate::complete;
 use crate::error::Result;
 use crate::output;
+use crate::queries::cycle::{CycleFilter, CycleQuery, CyclesQuery, CyclesVariables};
+use crate::queries::{IdVariables, TeamFilter};
+use crate::store::Store;
 
 #[derive(Subcommand)]
 pub enum CycleCommands {
@@ -16,12 +21,16 @@ pub enum CycleCommands {
 #[derive(Args)]
 pub struct ListCycleArgs {
     /// Filter by team key
-    #[arg(long)]
+    #[arg(long, add = ArgValueCandidates::new(complete::teams))]
     pub team: Option<String>,
 
     /// Maximum number to show
     #[arg(short = 'n', long, default_value = "10")]
     pub limit: u32,
+
+    /// Fetch every matching cycle, ignoring --limit
+    #[arg(long, conflicts_with = "limit")]
+    pub all: bool,
 }
 
 #[derive(Args)]
@@ -30,99 +39,45 @@ pub struct GetCycleArgs {
     pub id: String,
 }
 
-#[derive(Deserialize)]
-struct CyclesResponse {
-    cycles: CyclesConnection,
-}
-
-#[derive(Deserialize)]
-struct CyclesConnection {
-    nodes: Vec<Cycle>,
-}
-
-#[derive(Deserialize, Clone)]
-#[allow(dead_code)]
-pub struct Cycle {
-    pub id: String,
-    pub number: i32,
-    pub name: Option<String>,
-    #[serde(rename = "startsAt")]
-    pub starts_at: String,
-    #[serde(rename = "endsAt")]
-    pub ends_at: String,
-    pub progress: f64,
-}
-
-#[derive(Deserialize)]
-struct CycleResponse {
-    cycle: CycleDetail,
+pub async fn handle_list(client: &LinearClient, args: &ListCycleArgs) -> Result<()> {
+    let limit = (!args.all).then_some(args.limit);
+
+    client
+        .paginate_print(
+            limit,
+            |first, after| {
+                CyclesQuery::build(CyclesVariables {
+                    first,
+                    after,
+                    filter: CycleFilter {
+                        team: args.team.as_deref().map(TeamFilter::key),
+                        ..Default::default()
+                    },
+                })
+            },
+            |r| r.cycles.into(),
+            output::print_cycles,
+        )
+        .await
 }
 
-#[derive(Deserialize)]
-#[allow(dead_code)]
-pub struct CycleDetail {
-    pub id: String,
-    pub number: i32,
-    pub name: Option<String>,
-    #[serde(rename = "startsAt")]
-    pub starts_at: String,
-    #[serde(rename = "endsAt")]
-    pub ends_at: String,
-    pub progress: f64,
-    pub description: Option<String>,
+pub async fn handle_get(client: &LinearClient, args: &GetCycleArgs) -> Result<()> {
+    let response = client
+        .run(CycleQuery::build(IdVariables {
+            id: args.id.clone(),
+        }))
+        .await?;
+    output::print_cycle_detail(&response.cycle);
+    Ok(())
 }
 
-const CYCLES_QUERY: &str = r#"
-    query Cycles($first: Int, $filter: CycleFilter) {
-        cycles(first: $first, filter: $filter) {
-            nodes {
-                id
-                number
-                name
-                startsAt
-                endsAt
-                progress
-            }
-        }
-    }
-"#;
-
-const CYCLE_QUERY: &str = r#"
-    query Cycle($id: String!) {
-        cycle(id: $id) {
-            id
-            number
-            name
-            startsAt
-            endsAt
-            progress
-            description
-        }
-    }
-"#;
-
-pub async fn handle_list(client: &LinearClient, args: &ListCycleArgs) -> Result<()> {
-    let mut filter = serde_json::Map::new();
-    if let Some(team) = &args.team {
-        filter.insert(
-            "team".to_string(),
-            serde_json::json!({ "key": { "eq": team } }),
-        );
-    }
-
-    let variables = serde_json::json!({
-        "first": args.limit,
-        "filter": filter
-    });
-
-    let response: CyclesResponse = client.query(CYCLES_QUERY, variables).await?;
-    output::print_cycles(&response.cycles.nodes);
+pub fn handle_list_offline(store: &Store, args: &ListCycleArgs) -> Result<()> {
+    let limit = (!args.all).then_some(args.limit);
+    output::print_cycles(&store.cycles(args.team.as_deref(), limit)?);
     Ok(())
 }
 
-pub async fn handle_get(client: &LinearClient, args: &GetCycleArgs) -> Result<()> {
-    let variables = serde_json::json!({ "id": args.id });
-    let response: CycleResponse = client.query(CYCLE_QUERY, variables).await?;
-    output::print_cycle_detail(&response.cycle);
+pub fn handle_get_offline(store: &Store, args: &GetCycleArgs) -> Result<()> {
+    output::print_cycle_detail(&store.cycle(&args.id)?);
     Ok(())
 }
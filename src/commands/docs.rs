@@ -0,0 +1,636 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use clap::{Arg, ArgAction, Args, Command, CommandFactory, ValueEnum};
+use clap_mangen::roff::{bold, roman, Roff};
+use clap_mangen::Man;
+
+use crate::commands::update;
+use crate::error::{Error, Result};
+
+#[derive(Args)]
+pub struct DocsArgs {
+    /// Format to render the reference in
+    #[arg(value_enum, value_name = "FORMAT")]
+    pub kind: DocsFormat,
+
+    /// Directory to write the pages to, created if missing
+    #[arg(long, value_name = "DIR")]
+    pub out: PathBuf,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum DocsFormat {
+    /// One man page per command (linear.1, linear-issue.1, linear-issue-list.1, ...)
+    Man,
+    /// A single linear.md with every command
+    Markdown,
+}
+
+/// Examples shown with each command, as (command line, description). Every
+/// command line is parsed against the real arguments before it is rendered,
+/// so a renamed flag fails the docs build instead of going stale.
+const EXAMPLES: &[(&str, &[(&str, &str)])] = &[
+    (
+        "",
+        &[
+            ("linear --profile work issue list", "Use another profile"),
+            (
+                "linear --json issue get ENG-123",
+                "Print JSON instead of a table",
+            ),
+            (
+                "linear --offline search \"login bug\"",
+                "Search the local copy made by `linear sync`",
+            ),
+        ],
+    ),
+    (
+        "auth login",
+        &[
+            ("linear auth login", "Prompt for an API key and validate it"),
+            ("linear auth login --with-key", "Read the key from stdin"),
+            (
+                "linear auth login --oauth",
+                "Log in through the browser with an OAuth application",
+            ),
+        ],
+    ),
+    (
+        "auth logout",
+        &[("linear auth logout", "Remove the saved credential")],
+    ),
+    (
+        "auth status",
+        &[(
+            "linear auth status",
+            "Show the active credential and its user and workspace",
+        )],
+    ),
+    (
+        "user me",
+        &[("linear user me", "Show the authenticated user")],
+    ),
+    (
+        "issue list",
+        &[
+            ("linear issue list", "List your assigned issues"),
+            ("linear issue list --team ENG", "List a team's issues"),
+            (
+                "linear issue list --state \"In Progress\"",
+                "Filter by workflow state",
+            ),
+            ("linear issue list -n 50", "Show up to 50 issues"),
+            ("linear issue list --all", "Fetch every matching issue"),
+        ],
+    ),
+    (
+        "issue get",
+        &[("linear issue get ENG-123", "Show an issue")],
+    ),
+    (
+        "issue create",
+        &[
+            (
+                "linear issue create --team ENG --title \"Fix bug\"",
+                "Create an issue",
+            ),
+            (
+                "linear issue create --team ENG --title \"Task\" --description \"Details here\"",
+                "Create an issue with a description",
+            ),
+            (
+                "linear issue create --team ENG --title \"Checkout fails\" --label Bug --cycle current --estimate 3",
+                "Label, schedule and estimate a new issue",
+            ),
+            (
+                "linear issue create --team ENG --title \"Write tests\" --parent ENG-100 --assignee me",
+                "Create a sub-issue",
+            ),
+            (
+                "linear issue create",
+                "In a terminal, pick the team, state, assignee, labels and project from lists",
+            ),
+            (
+                "linear issue create -e",
+                "Write the title, team, labels and description in your editor",
+            ),
+            (
+                "linear issue create --title \"Release notes\" --description-file notes.md",
+                "Read the description from a file",
+            ),
+        ],
+    ),
+    (
+        "issue update",
+        &[
+            (
+                "linear issue update ENG-123 --state Done",
+                "Move an issue to another state",
+            ),
+            (
+                "linear issue update ENG-123 --assignee me",
+                "Assign an issue to yourself",
+            ),
+            (
+                "linear issue update ENG-123 --add-label Regression --remove-label Triage",
+                "Change an issue's labels",
+            ),
+            (
+                "linear issue update ENG-123 --assignee none --clear-due --no-parent",
+                "Unassign an issue and clear its due date and parent",
+            ),
+            (
+                "linear issue update ENG-123 --team DES",
+                "Move an issue to another team",
+            ),
+            (
+                "linear issue update ENG-123 -e",
+                "Edit an issue's title and description in your editor",
+            ),
+        ],
+    ),
+    ("team list", &[("linear team list", "List all teams")]),
+    ("team get", &[("linear team get ENG", "Show a team")]),
+    (
+        "project list",
+        &[
+            ("linear project list", "List all projects"),
+            ("linear project list --team ENG", "List a team's projects"),
+        ],
+    ),
+    (
+        "project get",
+        &[
+            ("linear project get \"Q1 Launch\"", "Show a project by name"),
+            (
+                "linear --offline project get \"Q1 Launch\"",
+                "Show a synced project without the network",
+            ),
+        ],
+    ),
+    (
+        "project create",
+        &[(
+            "linear project create --name \"Q1 Launch\" --team ENG",
+            "Create a project",
+        )],
+    ),
+    (
+        "cycle list",
+        &[
+            ("linear cycle list", "List cycles"),
+            ("linear cycle list --team ENG", "List a team's cycles"),
+        ],
+    ),
+    ("cycle get", &[("linear cycle get <id>", "Show a cycle")]),
+    (
+        "label list",
+        &[
+            ("linear label list", "List all labels"),
+            ("linear label list --team ENG", "List a team's labels"),
+        ],
+    ),
+    (
+        "workflow list",
+        &[(
+            "linear workflow list --team ENG",
+            "List a team's workflow states",
+        )],
+    ),
+    (
+        "comment list",
+        &[(
+            "linear comment list ENG-123",
+            "List the comments on an issue",
+        )],
+    ),
+    (
+        "comment create",
+        &[
+            (
+                "linear comment create --issue ENG-123 --body \"Looks good\"",
+                "Comment on an issue",
+            ),
+            (
+                "linear comment create --issue ENG-123 -e",
+                "Write a comment in your editor",
+            ),
+        ],
+    ),
+    (
+        "document list",
+        &[
+            ("linear document list", "List documents"),
+            (
+                "linear document list --project \"Q1 Launch\"",
+                "List a project's documents",
+            ),
+        ],
+    ),
+    (
+        "document get",
+        &[("linear document get <id>", "Show a document")],
+    ),
+    (
+        "document create",
+        &[
+            (
+                "linear document create --title \"Spec\" --project \"Q1 Launch\"",
+                "Create a document",
+            ),
+            (
+                "linear document create --title \"Spec\" --project \"Q1 Launch\" --content-file spec.md",
+                "Create a document from a Markdown file",
+            ),
+        ],
+    ),
+    (
+        "search",
+        &[
+            ("linear search \"bug fix\"", "Search issues"),
+            ("linear search login -n 20", "Show up to 20 results"),
+        ],
+    ),
+    (
+        "sync",
+        &[
+            ("linear sync", "Sync the whole workspace"),
+            ("linear sync --team ENG", "Sync one team's data"),
+        ],
+    ),
+    (
+        "api",
+        &[
+            (
+                "linear api \"query { viewer { name email } }\"",
+                "Run a query",
+            ),
+            (
+                "linear api @query.graphql -f id=ENG-123",
+                "Read the query from a file, with a string variable",
+            ),
+            (
+                "linear api @mutation.graphql -F input=@input.json",
+                "Pass a JSON variable from a file",
+            ),
+            (
+                "linear api @issues.graphql --paginate issues --jq \".issues.nodes[].identifier\"",
+                "Follow every page and filter the result",
+            ),
+        ],
+    ),
+    (
+        "api limits",
+        &[(
+            "linear api limits",
+            "Show the remaining request and complexity budget",
+        )],
+    ),
+    (
+        "cache status",
+        &[(
+            "linear cache status",
+            "Show what is cached and how old it is",
+        )],
+    ),
+    (
+        "cache clear",
+        &[
+            ("linear cache clear", "Clear the active profile's cache"),
+            ("linear cache clear --all", "Clear every profile's cache"),
+        ],
+    ),
+    (
+        "config get",
+        &[("linear config get default_team", "Print a value")],
+    ),
+    (
+        "config set",
+        &[
+            (
+                "linear config set default_team ENG",
+                "Set a value on the active profile",
+            ),
+            (
+                "linear --profile side-project config set api_key lin_api_xxxxx --make-default",
+                "Set up another profile and make it the default",
+            ),
+        ],
+    ),
+    (
+        "config unset",
+        &[("linear config unset issue_list.state", "Remove a value")],
+    ),
+    (
+        "config list",
+        &[("linear config list", "Show the active profile")],
+    ),
+    (
+        "config profiles",
+        &[(
+            "linear config profiles",
+            "List profiles (* marks the active one)",
+        )],
+    ),
+    (
+        "completion",
+        &[
+            (
+                "linear completion bash",
+                "Print the bash script, to source from ~/.bashrc",
+            ),
+            ("linear completion fish", "Print the fish script"),
+        ],
+    ),
+    (
+        "docs",
+        &[
+            ("linear docs man --out man", "Write man pages to ./man"),
+            ("linear docs markdown --out docs", "Write docs/linear.md"),
+        ],
+    ),
+    (
+        "update",
+        &[
+            ("linear update", "Update to the latest version"),
+            (
+                "linear update --check",
+                "Exit with code 10 if a newer version exists",
+            ),
+            (
+                "linear update --version v0.2.0",
+                "Install a specific release",
+            ),
+            (
+                "linear update --rollback",
+                "Restore the binary the last update replaced",
+            ),
+        ],
+    ),
+];
+
+/// Render the reference for every command from the command definitions.
+pub fn handle_docs(args: &DocsArgs) -> Result<()> {
+    check_examples()?;
+    fs::create_dir_all(&args.out)
+        .map_err(|e| Error::io(format!("failed to create {}", args.out.display()), e))?;
+
+    let root = command();
+    match args.kind {
+        DocsFormat::Man => {
+            let mut pages = 0;
+            write_man_pages(&root, &mut Vec::new(), &args.out, &mut pages)?;
+            println!("Wrote {} man pages to {}", pages, args.out.display());
+        }
+        DocsFormat::Markdown => {
+            let mut markdown = String::from(
+                "# Command reference\n\n\
+                 Generated by `linear docs markdown` from the command definitions.\n",
+            );
+            write_markdown(&root, &mut Vec::new(), &mut markdown);
+            let path = args.out.join("linear.md");
+            fs::write(&path, markdown)
+                .map_err(|e| Error::io(format!("failed to write {}", path.display()), e))?;
+            println!("Wrote {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// The full command tree, built so usage lines and page names include the
+/// parent commands.
+fn command() -> Command {
+    let mut command = crate::Cli::command().disable_help_subcommand(true);
+    command.build();
+    command
+}
+
+fn subcommands(command: &Command) -> impl Iterator<Item = &Command> {
+    command.get_subcommands().filter(|sub| !sub.is_hide_set())
+}
+
+fn examples(path: &[&str]) -> &'static [(&'static str, &'static str)] {
+    let path = path.join(" ");
+    EXAMPLES
+        .iter()
+        .find(|(command, _)| *command == path)
+        .map_or(&[], |(_, examples)| *examples)
+}
+
+fn check_examples() -> Result<()> {
+    for (_, examples) in EXAMPLES {
+        for (line, _) in *examples {
+            let words = shlex::split(line).ok_or_else(|| {
+                Error::InvalidInput(format!("unbalanced quotes in example `{}`", line))
+            })?;
+            crate::Cli::command()
+                .try_get_matches_from(words)
+                .map_err(|e| {
+                    Error::InvalidInput(format!("example `{}` doesn't parse: {}", line, e.kind()))
+                })?;
+        }
+    }
+    Ok(())
+}
+
+fn write_man_pages<'a>(
+    command: &'a Command,
+    path: &mut Vec<&'a str>,
+    out: &Path,
+    pages: &mut usize,
+) -> Result<()> {
+    let man = Man::new(command.clone()).source(format!("linear {}", update::CURRENT_VERSION));
+    let file = out.join(man.get_filename());
+    let mut page = Vec::new();
+    render_man(&man, command, examples(path), &mut page)
+        .and_then(|_| fs::File::create(&file)?.write_all(&page))
+        .map_err(|e| Error::io(format!("failed to write {}", file.display()), e))?;
+    *pages += 1;
+
+    for sub in subcommands(command) {
+        path.push(sub.get_name());
+        write_man_pages(sub, path, out, pages)?;
+        path.pop();
+    }
+    Ok(())
+}
+
+fn render_man(
+    man: &Man,
+    command: &Command,
+    examples: &[(&str, &str)],
+    w: &mut dyn Write,
+) -> std::io::Result<()> {
+    man.render_title(w)?;
+    man.render_name_section(w)?;
+    man.render_synopsis_section(w)?;
+    man.render_description_section(w)?;
+    if command.get_arguments().any(|arg| !arg.is_hide_set()) {
+        man.render_options_section(w)?;
+    }
+    if subcommands(command).next().is_some() {
+        man.render_subcommands_section(w)?;
+    }
+    if !examples.is_empty() {
+        let mut roff = Roff::new();
+        roff.control("SH", ["EXAMPLES"]);
+        for (line, description) in examples {
+            roff.control("TP", []);
+            roff.text([bold(*line)]);
+            roff.text([roman(*description)]);
+        }
+        roff.to_writer(w)?;
+    }
+    Ok(())
+}
+
+fn write_markdown<'a>(command: &'a Command, path: &mut Vec<&'a str>, out: &mut String) {
+    let name = std::iter::once("linear")
+        .chain(path.iter().copied())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let _ = writeln!(out, "\n## {}\n", name);
+    if let Some(about) = command.get_long_about().or(command.get_about()) {
+        let _ = writeln!(out, "{}\n", about);
+    }
+    let usage = command.clone().render_usage().to_string();
+    let _ = writeln!(out, "```text\n{}\n```\n", usage.trim_end());
+
+    // Global options are listed once, under `linear`
+    let (positionals, options): (Vec<&Arg>, Vec<&Arg>) = command
+        .get_arguments()
+        .filter(|arg| !arg.is_hide_set() && !is_help(arg))
+        .filter(|arg| path.is_empty() || !arg.is_global_set())
+        .partition(|arg| arg.is_positional());
+    if !positionals.is_empty() {
+        out.push_str("Arguments:\n\n");
+        for arg in positionals {
+            item(out, &positional(arg), arg);
+        }
+        out.push('\n');
+    }
+    if !options.is_empty() {
+        out.push_str("Options:\n\n");
+        for arg in options {
+            item(out, &flag(arg), arg);
+        }
+        out.push('\n');
+    }
+    if !path.is_empty() {
+        out.push_str("Also takes the [global options](#linear).\n\n");
+    }
+
+    let subs: Vec<&Command> = subcommands(command).collect();
+    if !subs.is_empty() {
+        out.push_str("Commands:\n\n");
+        for sub in &subs {
+            let title = format!("{} {}", name, sub.get_name());
+            let _ = writeln!(
+                out,
+                "- [`{}`](#{}): {}",
+                sub.get_name(),
+                title.replace(' ', "-"),
+                sub.get_about()
+                    .map(|about| about.to_string())
+                    .unwrap_or_default()
+            );
+        }
+        out.push('\n');
+    }
+
+    let examples = examples(path);
+    if !examples.is_empty() {
+        out.push_str("Examples:\n\n```bash\n");
+        for (line, description) in examples {
+            let _ = writeln!(out, "# {}\n{}", description, line);
+        }
+        out.push_str("```\n");
+    }
+
+    for sub in subs {
+        path.push(sub.get_name());
+        write_markdown(sub, path, out);
+        path.pop();
+    }
+}
+
+fn is_help(arg: &Arg) -> bool {
+    matches!(
+        arg.get_action(),
+        ArgAction::Help | ArgAction::HelpShort | ArgAction::HelpLong
+    )
+}
+
+fn positional(arg: &Arg) -> String {
+    let name = value_names(arg).join(" ");
+    let name = if arg.is_required_set() {
+        format!("<{}>", name)
+    } else {
+        format!("[{}]", name)
+    };
+    if matches!(arg.get_action(), ArgAction::Append) {
+        format!("{}...", name)
+    } else {
+        name
+    }
+}
+
+fn flag(arg: &Arg) -> String {
+    let mut names = Vec::new();
+    if let Some(short) = arg.get_short() {
+        names.push(format!("-{}", short));
+    }
+    if let Some(long) = arg.get_long() {
+        names.push(format!("--{}", long));
+    }
+    let mut flag = names.join(", ");
+    if arg.get_action().takes_values() {
+        for value in value_names(arg) {
+            let _ = write!(flag, " <{}>", value);
+        }
+    }
+    flag
+}
+
+fn value_names(arg: &Arg) -> Vec<String> {
+    match arg.get_value_names() {
+        Some(names) => names.iter().map(|name| name.to_string()).collect(),
+        None => vec![arg.get_id().as_str().to_uppercase()],
+    }
+}
+
+/// One argument as a list item: its help, then its default, environment
+/// variable and possible values.
+fn item(out: &mut String, name: &str, arg: &Arg) {
+    let help = arg
+        .get_long_help()
+        .or(arg.get_help())
+        .map(|help| help.to_string().replace('\n', " "))
+        .unwrap_or_default();
+    let _ = write!(out, "- `{}`: {}", name, help);
+
+    let defaults: Vec<String> = arg
+        .get_default_values()
+        .iter()
+        .map(|value| value.to_string_lossy().into_owned())
+        .collect();
+    if !defaults.is_empty() && arg.get_action().takes_values() {
+        let _ = write!(out, " [default: {}]", defaults.join(","));
+    }
+    if let Some(env) = arg.get_env() {
+        let _ = write!(out, " [env: {}]", env.to_string_lossy());
+    }
+    let values: Vec<String> = arg
+        .get_possible_values()
+        .iter()
+        .filter(|value| !value.is_hide_set())
+        .map(|value| value.get_name().to_string())
+        .collect();
+    if !values.is_empty() && arg.get_action().takes_values() {
+        let _ = write!(out, " [possible values: {}]", values.join(", "));
+    }
+    out.push('\n');
+}
@@ -1,9 +1,20 @@
 use clap::{Args, Subcommand};
-use serde::Deserialize;
+use clap_complete::ArgValueCandidates;
+use cynic::{MutationBuilder, QueryBuilder};
 
 use crate::client::LinearClient;
-use crate::error::Result;
+use crate::complete;
+use crate::error::{Error, Result};
+use crate::input::{self, Draft};
 use crate::output;
+use crate::prompt;
+use crate::queries::document::{
+    CreateDocumentMutation, CreateDocumentVariables, DocumentCreateInput, DocumentFilter,
+    DocumentQuery, DocumentsQuery, DocumentsVariables,
+};
+use crate::queries::project::ProjectFilter;
+use crate::queries::{IdComparator, IdVariables};
+use crate::resolve;
 
 #[derive(Subcommand)]
 pub enum DocumentCommands {
@@ -18,12 +29,16 @@ pub enum DocumentCommands {
 #[derive(Args)]
 pub struct ListDocumentArgs {
     /// Filter by project name or ID
-    #[arg(long)]
+    #[arg(long, add = ArgValueCandidates::new(complete::projects))]
     pub project: Option<String>,
 
     /// Maximum number to show
     #[arg(short = 'n', long, default_value = "25")]
     pub limit: u32,
+
+    /// Fetch every matching document, ignoring --limit
+    #[arg(long, conflicts_with = "limit")]
+    pub all: bool,
 }
 
 #[derive(Args)]
@@ -32,160 +47,147 @@ pub struct GetDocumentArgs {
     pub id: String,
 }
 
-#[derive(Args)]
+#[derive(Args, Clone)]
 pub struct CreateDocumentArgs {
-    /// Document title
+    /// Document title [prompted for in a terminal]
     #[arg(long)]
-    pub title: String,
+    pub title: Option<String>,
 
-    /// Project ID to attach document to
-    #[arg(long)]
-    pub project: String,
+    /// Project name or ID to attach document to [prompted for in a terminal]
+    #[arg(long, add = ArgValueCandidates::new(complete::projects))]
+    pub project: Option<String>,
 
     /// Document content (markdown)
     #[arg(long)]
     pub content: Option<String>,
-}
-
-#[derive(Deserialize)]
-struct DocumentsResponse {
-    documents: DocumentsConnection,
-}
-
-#[derive(Deserialize)]
-struct DocumentsConnection {
-    nodes: Vec<Document>,
-}
-
-#[derive(Deserialize, Clone)]
-pub struct Document {
-    #[allow(dead_code)]
-    pub id: String,
-    pub title: String,
-    #[serde(rename = "updatedAt")]
-    pub updated_at: String,
-}
-
-#[derive(Deserialize)]
-struct DocumentResponse {
-    document: DocumentDetail,
-}
-
-#[derive(Deserialize)]
-pub struct DocumentDetail {
-    #[allow(dead_code)]
-    pub id: String,
-    pub title: String,
-    pub content: Option<String>,
-    #[serde(rename = "createdAt")]
-    pub created_at: String,
-    #[serde(rename = "updatedAt")]
-    pub updated_at: String,
-}
-
-#[derive(Deserialize)]
-struct CreateDocumentResponse {
-    #[serde(rename = "documentCreate")]
-    document_create: DocumentCreatePayload,
-}
 
-#[derive(Deserialize)]
-struct DocumentCreatePayload {
-    success: bool,
-    document: Option<CreatedDocument>,
-}
+    /// Read the content from a file ("-" for stdin)
+    #[arg(long, value_name = "FILE", conflicts_with = "content")]
+    pub content_file: Option<String>,
 
-#[derive(Deserialize)]
-struct CreatedDocument {
-    id: String,
-    title: String,
+    /// Write the title and content in $VISUAL or $EDITOR [default: when no
+    /// content is given in a terminal]
+    #[arg(short, long)]
+    pub editor: bool,
 }
 
-const DOCUMENTS_QUERY: &str = r#"
-    query Documents($first: Int, $filter: DocumentFilter) {
-        documents(first: $first, filter: $filter) {
-            nodes {
-                id
-                title
-                updatedAt
-            }
-        }
-    }
-"#;
-
-const DOCUMENT_QUERY: &str = r#"
-    query Document($id: String!) {
-        document(id: $id) {
-            id
-            title
-            content
-            createdAt
-            updatedAt
-        }
-    }
-"#;
-
-const CREATE_DOCUMENT_MUTATION: &str = r#"
-    mutation CreateDocument($input: DocumentCreateInput!) {
-        documentCreate(input: $input) {
-            success
-            document {
-                id
-                title
-            }
-        }
-    }
-"#;
-
 pub async fn handle_list(client: &LinearClient, args: &ListDocumentArgs) -> Result<()> {
-    let mut filter = serde_json::Map::new();
-    if let Some(project) = &args.project {
-        filter.insert(
-            "project".to_string(),
-            serde_json::json!({ "id": { "eq": project } }),
-        );
-    }
-
-    let variables = serde_json::json!({
-        "first": args.limit,
-        "filter": filter
-    });
-
-    let response: DocumentsResponse = client.query(DOCUMENTS_QUERY, variables).await?;
-    output::print_documents(&response.documents.nodes);
-    Ok(())
+    let project_id = match &args.project {
+        Some(project) => Some(resolve::project(client, project).await?),
+        None => None,
+    };
+    let limit = (!args.all).then_some(args.limit);
+
+    client
+        .paginate_print(
+            limit,
+            |first, after| {
+                DocumentsQuery::build(DocumentsVariables {
+                    first,
+                    after,
+                    filter: DocumentFilter {
+                        project: project_id.as_deref().map(|id| ProjectFilter {
+                            id: Some(IdComparator::eq(id)),
+                            ..Default::default()
+                        }),
+                    },
+                })
+            },
+            |r| r.documents.into(),
+            output::print_documents,
+        )
+        .await
 }
 
 pub async fn handle_get(client: &LinearClient, args: &GetDocumentArgs) -> Result<()> {
-    let variables = serde_json::json!({ "id": args.id });
-    let response: DocumentResponse = client.query(DOCUMENT_QUERY, variables).await?;
+    let response = client
+        .run(DocumentQuery::build(IdVariables {
+            id: args.id.clone(),
+        }))
+        .await?;
     output::print_document_detail(&response.document);
     Ok(())
 }
 
 pub async fn handle_create(client: &LinearClient, args: &CreateDocumentArgs) -> Result<()> {
-    let mut input = serde_json::Map::new();
-    input.insert("title".to_string(), serde_json::json!(args.title));
-    input.insert("projectId".to_string(), serde_json::json!(args.project));
-
-    if let Some(content) = &args.content {
-        input.insert("content".to_string(), serde_json::json!(content));
+    let mut args = args.clone();
+    args.content = input::text(args.content.as_deref(), args.content_file.as_deref())?;
+    let edited = input::wants_editor(args.editor, args.content.is_none());
+    if edited {
+        let draft = Draft::new(args.content.as_deref()).field("title", args.title.as_deref());
+        let draft = input::edit("document", &draft)?;
+        let title = draft
+            .get("title")
+            .ok_or_else(|| Error::InvalidInput("the title is empty".to_string()))?;
+        args.title = Some(title.to_string());
+        args.content = draft.body().map(str::to_string);
     }
-
-    let variables = serde_json::json!({ "input": input });
-    let response: CreateDocumentResponse =
-        client.query(CREATE_DOCUMENT_MUTATION, variables).await?;
+    if (args.title.is_none() || args.project.is_none()) && input::interactive() {
+        prompt_new_document(client, &mut args, edited).await?;
+    }
+    let title = args
+        .title
+        .clone()
+        .ok_or_else(|| input::missing("--title"))?;
+    let project = args
+        .project
+        .as_deref()
+        .ok_or_else(|| input::missing("--project"))?;
+    let project_id = resolve::project(client, project).await?;
+
+    let operation = CreateDocumentMutation::build(CreateDocumentVariables {
+        input: DocumentCreateInput {
+            title,
+            project_id,
+            content: args.content,
+        },
+    });
+    let response = client.run(operation).await?;
 
     if response.document_create.success {
         if let Some(doc) = response.document_create.document {
-            println!("Created document: {}", doc.title);
-            println!("ID: {}", doc.id);
+            output::print_created_document(&doc);
         }
     } else {
-        return Err(crate::error::Error::GraphQL(
-            "Failed to create document".to_string(),
-        ));
+        return Err(Error::GraphQL("Failed to create document".to_string()));
     }
 
     Ok(())
 }
+
+/// Ask for the title and project if missing, and offer to write the content
+/// in the editor unless it was just `edited`, then confirm.
+async fn prompt_new_document(
+    client: &LinearClient,
+    args: &mut CreateDocumentArgs,
+    edited: bool,
+) -> Result<()> {
+    let title = match args.title.take() {
+        Some(title) => title,
+        None => prompt::text("Title")?,
+    };
+    let project = match &args.project {
+        Some(project) => project.clone(),
+        None => {
+            let projects = resolve::projects(client).await?;
+            let index = prompt::select("Project", &projects, |p| p.name.clone())?;
+            args.project = Some(projects[index].id.inner().to_string());
+            projects[index].name.clone()
+        }
+    };
+    args.title = Some(title.clone());
+
+    if !edited
+        && args.content.is_none()
+        && prompt::confirm("Write the content in your editor?", false)?
+    {
+        args.content = input::edit("document", &Draft::new(None))?
+            .body()
+            .map(str::to_string);
+    }
+    let content = prompt::lines(args.content.as_deref());
+    prompt::confirm_summary(
+        "Create this document?",
+        &[("Title", title), ("Project", project), ("Content", content)],
+    )
+}
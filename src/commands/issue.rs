@@ -1,8 +1,24 @@
 use crate::client::LinearClient;
-use crate::error::Result;
+use crate::complete;
+use crate::config::{IssueListDefaults, Profile};
+use crate::error::{Error, Result};
+use crate::input::{self, Draft};
 use crate::output;
+use crate::prompt;
+use crate::queries::issue::{
+    AddLabelMutation, CreateIssueMutation, CreateIssueVariables, IssueCreateInput,
+    IssueFieldsQuery, IssueFilter, IssueLabelVariables, IssueQuery, IssueUpdateInput,
+    IssueUpdatePayload, IssuesQuery, IssuesVariables, NullableUserFilter, RemoveLabelMutation,
+    UpdateIssueMutation, UpdateIssueVariables, UpdatedIssue, WorkflowStateFilter,
+};
+use crate::queries::resolve::{TeamRef, UserRef};
+use crate::queries::{BooleanComparator, IdVariables, Patch, StringComparator, TeamFilter};
+use crate::resolve;
+use crate::store::Store;
 use clap::{Args, Subcommand};
-use serde::Deserialize;
+use clap_complete::ArgValueCandidates;
+use cynic::{MutationBuilder, QueryBuilder};
+use serde::Serialize;
 
 #[derive(Subcommand)]
 pub enum IssueCommands {
@@ -11,343 +27,744 @@ pub enum IssueCommands {
     /// Get issue details
     Get(GetArgs),
     /// Create a new issue
-    Create(CreateArgs),
+    Create(Box<CreateArgs>),
     /// Update an issue
-    Update(UpdateArgs),
+    Update(Box<UpdateArgs>),
 }
 
 #[derive(Args)]
 pub struct ListArgs {
     /// Filter by team key (e.g., ENG)
-    #[arg(long)]
+    #[arg(long, add = ArgValueCandidates::new(complete::teams))]
     pub team: Option<String>,
 
     /// Filter by state name (e.g., "In Progress")
-    #[arg(long)]
+    #[arg(long, add = ArgValueCandidates::new(complete::states))]
     pub state: Option<String>,
 
     /// Filter by assignee (use "me" for yourself)
     #[arg(long)]
     pub assignee: Option<String>,
 
-    /// Maximum number of issues to show
-    #[arg(short = 'n', long, default_value = "25")]
-    pub limit: u32,
+    /// Maximum number of issues to show [default: 25]
+    #[arg(short = 'n', long)]
+    pub limit: Option<u32>,
+
+    /// Fetch every matching issue, ignoring --limit
+    #[arg(long, conflicts_with = "limit")]
+    pub all: bool,
 }
 
 #[derive(Args)]
 pub struct GetArgs {
     /// Issue identifier (e.g., ENG-123)
+    #[arg(add = ArgValueCandidates::new(complete::issues))]
     pub id: String,
 }
 
-#[derive(Args)]
+#[derive(Args, Clone)]
 pub struct CreateArgs {
-    /// Issue title
+    /// Issue title [prompted for in a terminal]
     #[arg(long)]
-    pub title: String,
+    pub title: Option<String>,
 
-    /// Team key (e.g., ENG)
-    #[arg(long)]
-    pub team: String,
+    /// Team key (e.g., ENG) [default: the profile's default_team]
+    #[arg(long, add = ArgValueCandidates::new(complete::teams))]
+    pub team: Option<String>,
 
     /// Issue description (markdown)
     #[arg(long)]
     pub description: Option<String>,
 
+    /// Read the description from a file ("-" for stdin)
+    #[arg(long, value_name = "FILE", conflicts_with = "description")]
+    pub description_file: Option<String>,
+
+    /// Write the title, team, labels and description in $VISUAL or $EDITOR
+    /// [default: when no description is given in a terminal]
+    #[arg(short, long)]
+    pub editor: bool,
+
     /// Priority (1=urgent, 2=high, 3=normal, 4=low)
     #[arg(long)]
     pub priority: Option<i32>,
+
+    /// Assignee name or email (use "me" for yourself)
+    #[arg(long)]
+    pub assignee: Option<String>,
+
+    /// State name (e.g., "Todo") [default: the team's default state]
+    #[arg(long, add = ArgValueCandidates::new(complete::states))]
+    pub state: Option<String>,
+
+    /// Label name (repeatable)
+    #[arg(long = "label", value_name = "LABEL", add = ArgValueCandidates::new(complete::labels))]
+    pub labels: Vec<String>,
+
+    /// Project name or ID
+    #[arg(long, add = ArgValueCandidates::new(complete::projects))]
+    pub project: Option<String>,
+
+    /// Project milestone name
+    #[arg(long, requires = "project")]
+    pub milestone: Option<String>,
+
+    /// Cycle number, or "current" or "next"
+    #[arg(long)]
+    pub cycle: Option<String>,
+
+    /// Estimate in points on the team's scale, or a T-shirt size (e.g., M)
+    #[arg(long)]
+    pub estimate: Option<String>,
+
+    /// Due date (YYYY-MM-DD)
+    #[arg(long, value_name = "DATE")]
+    pub due: Option<String>,
+
+    /// Parent issue identifier (e.g., ENG-100)
+    #[arg(long, add = ArgValueCandidates::new(complete::issues))]
+    pub parent: Option<String>,
+
+    /// Subscriber name or email (repeatable)
+    #[arg(long = "subscriber", value_name = "USER")]
+    pub subscribers: Vec<String>,
+
+    /// Issue template name to apply
+    #[arg(long)]
+    pub template: Option<String>,
 }
 
 #[derive(Args)]
 pub struct UpdateArgs {
     /// Issue identifier (e.g., ENG-123)
+    #[arg(add = ArgValueCandidates::new(complete::issues))]
     pub id: String,
 
     /// New title
     #[arg(long)]
     pub title: Option<String>,
 
-    /// New state name (e.g., "Done")
+    /// New description (markdown)
     #[arg(long)]
+    pub description: Option<String>,
+
+    /// Read the new description from a file ("-" for stdin)
+    #[arg(long, value_name = "FILE", conflicts_with = "description")]
+    pub description_file: Option<String>,
+
+    /// Edit the title and description in $VISUAL or $EDITOR
+    #[arg(short, long)]
+    pub editor: bool,
+
+    /// New state name (e.g., "Done")
+    #[arg(long, add = ArgValueCandidates::new(complete::states))]
     pub state: Option<String>,
 
+    /// New assignee name or email (use "me" for yourself, "none" to unassign)
+    #[arg(long)]
+    pub assignee: Option<String>,
+
     /// New priority (1=urgent, 2=high, 3=normal, 4=low)
     #[arg(long)]
     pub priority: Option<i32>,
-}
 
-#[derive(Deserialize)]
-struct IssuesResponse {
-    issues: IssuesConnection,
-}
+    /// Add a label (repeatable)
+    #[arg(long = "add-label", value_name = "LABEL", add = ArgValueCandidates::new(complete::labels))]
+    pub add_labels: Vec<String>,
 
-#[derive(Deserialize)]
-struct IssuesConnection {
-    nodes: Vec<Issue>,
-}
+    /// Remove a label (repeatable)
+    #[arg(long = "remove-label", value_name = "LABEL", add = ArgValueCandidates::new(complete::labels))]
+    pub remove_labels: Vec<String>,
 
-#[derive(Deserialize)]
-pub struct Issue {
-    pub identifier: String,
-    pub title: String,
-    pub state: Option<IssueState>,
-    pub assignee: Option<Assignee>,
-    pub priority: i32,
-}
+    /// Move to a project, by name or ID
+    #[arg(long, add = ArgValueCandidates::new(complete::projects))]
+    pub project: Option<String>,
+
+    /// Move to a cycle: its number, or "current" or "next"
+    #[arg(long)]
+    pub cycle: Option<String>,
+
+    /// New estimate in points on the team's scale, or a T-shirt size (e.g., M)
+    #[arg(long)]
+    pub estimate: Option<String>,
+
+    /// New due date (YYYY-MM-DD)
+    #[arg(long, value_name = "DATE", conflicts_with = "clear_due")]
+    pub due: Option<String>,
 
-#[derive(Deserialize)]
-pub struct IssueState {
-    pub name: String,
+    /// Remove the due date
+    #[arg(long)]
+    pub clear_due: bool,
+
+    /// Make it a sub-issue of this issue (e.g., ENG-100)
+    #[arg(long, add = ArgValueCandidates::new(complete::issues), conflicts_with = "no_parent")]
+    pub parent: Option<String>,
+
+    /// Detach it from its parent issue
+    #[arg(long)]
+    pub no_parent: bool,
+
+    /// Move it to another team, by key (e.g., ENG)
+    #[arg(long, add = ArgValueCandidates::new(complete::teams))]
+    pub team: Option<String>,
 }
 
-#[derive(Deserialize)]
-pub struct Assignee {
-    pub name: String,
+impl UpdateArgs {
+    /// Whether any field set through `issueUpdate` is given, as opposed to
+    /// labels, which have mutations of their own.
+    fn updates_fields(&self) -> bool {
+        self.title.is_some()
+            || self.description.is_some()
+            || self.description_file.is_some()
+            || self.editor
+            || self.state.is_some()
+            || self.assignee.is_some()
+            || self.priority.is_some()
+            || self.project.is_some()
+            || self.cycle.is_some()
+            || self.estimate.is_some()
+            || self.due.is_some()
+            || self.clear_due
+            || self.parent.is_some()
+            || self.no_parent
+            || self.team.is_some()
+    }
 }
 
-#[derive(Deserialize)]
-struct IssueResponse {
-    issue: IssueDetail,
+/// One field `issue update` changed.
+#[derive(Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub before: Option<String>,
+    pub after: Option<String>,
 }
 
-#[derive(Deserialize)]
-pub struct IssueDetail {
+#[derive(Serialize)]
+pub struct IssueChanges {
     pub identifier: String,
     pub title: String,
-    pub description: Option<String>,
-    pub state: Option<IssueState>,
-    pub assignee: Option<Assignee>,
-    pub priority: i32,
-    #[serde(rename = "createdAt")]
-    pub created_at: String,
-    #[serde(rename = "updatedAt")]
-    pub updated_at: String,
-    pub team: Team,
+    pub changes: Vec<FieldChange>,
 }
 
-#[derive(Deserialize)]
-pub struct Team {
-    pub key: String,
-    pub name: String,
+pub async fn handle_list(client: &LinearClient, args: &ListArgs, profile: &Profile) -> Result<()> {
+    let defaults = &profile.issue_list;
+    let limit = (!args.all).then(|| args.limit.or(defaults.limit).unwrap_or(25));
+
+    client
+        .paginate_print(
+            limit,
+            |first, after| {
+                IssuesQuery::build(IssuesVariables {
+                    first,
+                    after,
+                    filter: build_filter(args, defaults),
+                })
+            },
+            |r| r.issues.into(),
+            output::print_issues,
+        )
+        .await
 }
 
-const ISSUES_QUERY: &str = r#"
-    query Issues($first: Int, $filter: IssueFilter) {
-        issues(first: $first, filter: $filter) {
-            nodes {
-                identifier
-                title
-                state { name }
-                assignee { name }
-                priority
-            }
-        }
-    }
-"#;
-
-const ISSUE_QUERY: &str = r#"
-    query Issue($id: String!) {
-        issue(id: $id) {
-            identifier
-            title
-            description
-            state { name }
-            assignee { name }
-            priority
-            createdAt
-            updatedAt
-            team { key name }
-        }
-    }
-"#;
-
-#[derive(Deserialize)]
-struct CreateIssueResponse {
-    #[serde(rename = "issueCreate")]
-    issue_create: IssueCreatePayload,
+pub async fn handle_get(client: &LinearClient, args: &GetArgs) -> Result<()> {
+    let response = client
+        .run(IssueQuery::build(IdVariables {
+            id: args.id.clone(),
+        }))
+        .await?;
+    output::print_issue_detail(&response.issue);
+    Ok(())
 }
 
-#[derive(Deserialize)]
-struct IssueCreatePayload {
-    success: bool,
-    issue: Option<CreatedIssue>,
+pub fn handle_list_offline(store: &Store, args: &ListArgs, profile: &Profile) -> Result<()> {
+    let defaults = &profile.issue_list;
+    let limit = (!args.all).then(|| args.limit.or(defaults.limit).unwrap_or(25));
+    let issues = store.issues(
+        args.team.as_deref().or(defaults.team.as_deref()),
+        args.state.as_deref().or(defaults.state.as_deref()),
+        args.assignee.as_deref().or(defaults.assignee.as_deref()),
+        limit,
+    )?;
+    output::print_issues(&issues);
+    Ok(())
 }
 
-#[derive(Deserialize)]
-struct CreatedIssue {
-    identifier: String,
-    title: String,
-    url: String,
+pub fn handle_get_offline(store: &Store, args: &GetArgs) -> Result<()> {
+    output::print_issue_detail(&store.issue(&args.id)?);
+    Ok(())
 }
 
-const CREATE_ISSUE_MUTATION: &str = r#"
-    mutation CreateIssue($input: IssueCreateInput!) {
-        issueCreate(input: $input) {
-            success
-            issue {
-                identifier
-                title
-                url
-            }
+pub async fn handle_create(
+    client: &LinearClient,
+    args: &CreateArgs,
+    profile: &Profile,
+) -> Result<()> {
+    let mut args = args.clone();
+    args.description = input::text(
+        args.description.as_deref(),
+        args.description_file.as_deref(),
+    )?;
+    let edited = input::wants_editor(args.editor, args.description.is_none());
+    if edited {
+        edit_new_issue(&mut args, profile)?;
+    }
+    if (args.title.is_none() || profile.team(args.team.as_deref()).is_err()) && input::interactive()
+    {
+        prompt_new_issue(client, &mut args, profile, edited).await?;
+    }
+    let title = args
+        .title
+        .clone()
+        .ok_or_else(|| input::missing("--title"))?;
+
+    let team = resolve::team(client, profile.team(args.team.as_deref())?).await?;
+    let team_id = team.id.inner();
+
+    let mut input = IssueCreateInput {
+        title,
+        team_id: team_id.to_string(),
+        description: args.description.clone(),
+        priority: args.priority,
+        due_date: args.due.as_deref().map(due_date).transpose()?,
+        ..Default::default()
+    };
+    if let Some(state) = &args.state {
+        let team = TeamRef {
+            id: team.id.clone(),
+            key: team.key.clone(),
+        };
+        input.state_id = Some(resolve::state(client, &team, state).await?);
+    }
+    if let Some(assignee) = &args.assignee {
+        input.assignee_id = Some(resolve::user(client, assignee).await?);
+    }
+    if !args.labels.is_empty() {
+        input.label_ids = Some(resolve::labels(client, team_id, &args.labels).await?);
+    }
+    if let Some(project) = &args.project {
+        let project_id = resolve::project(client, project).await?;
+        if let Some(milestone) = &args.milestone {
+            input.project_milestone_id =
+                Some(resolve::milestone(client, &project_id, milestone).await?);
         }
+        input.project_id = Some(project_id);
+    }
+    if args.cycle.is_some() || args.estimate.is_some() {
+        let settings = resolve::team_settings(client, team_id).await?;
+        if let Some(estimate) = &args.estimate {
+            input.estimate = Some(resolve::estimate(&settings, estimate)?);
+        }
+        if let Some(cycle) = &args.cycle {
+            input.cycle_id = Some(resolve::cycle(client, &settings, cycle).await?);
+        }
+    }
+    if let Some(parent) = &args.parent {
+        input.parent_id = Some(resolve::issue(client, parent).await?.id.into_inner());
+    }
+    if !args.subscribers.is_empty() {
+        let mut ids = Vec::new();
+        for subscriber in &args.subscribers {
+            ids.push(resolve::user(client, subscriber).await?);
+        }
+        input.subscriber_ids = Some(ids);
+    }
+    if let Some(template) = &args.template {
+        input.template_id = Some(resolve::template(client, team_id, template).await?);
     }
-"#;
-
-#[derive(Deserialize)]
-struct UpdateIssueResponse {
-    #[serde(rename = "issueUpdate")]
-    issue_update: IssueUpdatePayload,
-}
-
-#[derive(Deserialize)]
-struct IssueUpdatePayload {
-    success: bool,
-    issue: Option<UpdatedIssue>,
-}
 
-#[derive(Deserialize)]
-struct UpdatedIssue {
-    identifier: String,
-    title: String,
-    state: Option<IssueState>,
-}
+    let operation = CreateIssueMutation::build(CreateIssueVariables { input });
+    let response = client.run(operation).await?;
 
-const UPDATE_ISSUE_MUTATION: &str = r#"
-    mutation UpdateIssue($id: String!, $input: IssueUpdateInput!) {
-        issueUpdate(id: $id, input: $input) {
-            success
-            issue {
-                identifier
-                title
-                state { name }
-            }
+    if response.issue_create.success {
+        if let Some(issue) = response.issue_create.issue {
+            output::print_created_issue(&issue);
         }
+    } else {
+        return Err(Error::GraphQL("Failed to create issue".to_string()));
     }
-"#;
-
-pub async fn handle_list(client: &LinearClient, args: &ListArgs) -> Result<()> {
-    let filter = build_filter(args);
-    let variables = serde_json::json!({
-        "first": args.limit,
-        "filter": filter
-    });
 
-    let response: IssuesResponse = client.query(ISSUES_QUERY, variables).await?;
-    output::print_issues(&response.issues.nodes);
     Ok(())
 }
 
-pub async fn handle_get(client: &LinearClient, args: &GetArgs) -> Result<()> {
-    let variables = serde_json::json!({ "id": args.id });
-    let response: IssueResponse = client.query(ISSUE_QUERY, variables).await?;
-    output::print_issue_detail(&response.issue);
+/// Fill in the title, team, labels and description from the editor.
+fn edit_new_issue(args: &mut CreateArgs, profile: &Profile) -> Result<()> {
+    let labels = args.labels.join(", ");
+    let draft = Draft::new(args.description.as_deref())
+        .field("title", args.title.as_deref())
+        .field(
+            "team",
+            args.team.as_deref().or(profile.default_team.as_deref()),
+        )
+        .field("labels", Some(&labels));
+    let draft = input::edit("issue", &draft)?;
+
+    let title = draft
+        .get("title")
+        .ok_or_else(|| Error::InvalidInput("the title is empty".to_string()))?;
+    args.title = Some(title.to_string());
+    args.team = draft.get("team").map(str::to_string);
+    args.labels = draft.get("labels").map_or_else(Vec::new, |labels| {
+        labels
+            .split(',')
+            .map(str::trim)
+            .filter(|label| !label.is_empty())
+            .map(str::to_string)
+            .collect()
+    });
+    args.description = draft.body().map(str::to_string);
     Ok(())
 }
 
-pub async fn handle_create(client: &LinearClient, args: &CreateArgs) -> Result<()> {
-    let mut input = serde_json::Map::new();
-    input.insert("title".to_string(), serde_json::json!(args.title));
-    input.insert("teamId".to_string(), serde_json::json!(args.team));
-
-    if let Some(desc) = &args.description {
-        input.insert("description".to_string(), serde_json::json!(desc));
-    }
-    if let Some(priority) = args.priority {
-        input.insert("priority".to_string(), serde_json::json!(priority));
+/// Ask for the title and team if missing, and for the state, assignee,
+/// labels, project and description not given as flags, then confirm. What
+/// is picked is stored as IDs, which the resolvers accept like names. The
+/// description isn't offered again when it was just `edited`.
+async fn prompt_new_issue(
+    client: &LinearClient,
+    args: &mut CreateArgs,
+    profile: &Profile,
+    edited: bool,
+) -> Result<()> {
+    let title = match args.title.take() {
+        Some(title) => title,
+        None => prompt::text("Title")?,
+    };
+    let team = match profile.team(args.team.as_deref()) {
+        Ok(team) => resolve::team(client, team).await?,
+        Err(_) => {
+            let mut teams = resolve::teams(client).await?;
+            let index = prompt::select("Team", &teams, |t| format!("{} {}", t.key, t.name))?;
+            teams.swap_remove(index)
+        }
+    };
+    let team_id = team.id.inner();
+    let mut summary = vec![
+        ("Title", title.clone()),
+        ("Team", format!("{} ({})", team.name, team.key)),
+    ];
+    args.title = Some(title);
+    args.team = Some(team.key.clone());
+
+    let state = match &args.state {
+        Some(state) => state.clone(),
+        None => {
+            let states = resolve::states(client, team_id).await?;
+            match prompt::select_optional("State", "Team default", &states, |s| s.name.clone())? {
+                Some(index) => {
+                    args.state = Some(states[index].id.inner().to_string());
+                    states[index].name.clone()
+                }
+                None => "Team default".to_string(),
+            }
+        }
+    };
+    summary.push(("State", state));
+
+    let assignee = match &args.assignee {
+        Some(assignee) => assignee.clone(),
+        None => {
+            let users = resolve::users(client).await?;
+            let label = |u: &UserRef| format!("{} <{}>", u.name, u.email);
+            match prompt::select_optional("Assignee", "Unassigned", &users, label)? {
+                Some(index) => {
+                    args.assignee = Some(users[index].id.inner().to_string());
+                    users[index].name.clone()
+                }
+                None => "Unassigned".to_string(),
+            }
+        }
+    };
+    summary.push(("Assignee", assignee));
+
+    if args.labels.is_empty() {
+        let labels = resolve::team_labels(client, team_id).await?;
+        if !labels.is_empty() {
+            let picked = prompt::select_many("Labels", &labels, |l| l.name.clone())?;
+            args.labels = picked
+                .iter()
+                .map(|&index| labels[index].id.inner().to_string())
+                .collect();
+            let names: Vec<&str> = picked
+                .iter()
+                .map(|&index| labels[index].name.as_str())
+                .collect();
+            summary.push(("Labels", names.join(", ")));
+        }
+    } else {
+        summary.push(("Labels", args.labels.join(", ")));
     }
 
-    let variables = serde_json::json!({ "input": input });
-    let response: CreateIssueResponse = client.query(CREATE_ISSUE_MUTATION, variables).await?;
-
-    if response.issue_create.success {
-        if let Some(issue) = response.issue_create.issue {
-            println!("Created {} - {}", issue.identifier, issue.title);
-            println!("{}", issue.url);
+    let project = match &args.project {
+        Some(project) => project.clone(),
+        None => {
+            let projects = resolve::projects(client).await?;
+            match prompt::select_optional("Project", "No project", &projects, |p| p.name.clone())? {
+                Some(index) => {
+                    args.project = Some(projects[index].id.inner().to_string());
+                    projects[index].name.clone()
+                }
+                None => "No project".to_string(),
+            }
         }
-    } else {
-        return Err(crate::error::Error::GraphQL(
-            "Failed to create issue".to_string(),
-        ));
+    };
+    summary.push(("Project", project));
+
+    if !edited
+        && args.description.is_none()
+        && prompt::confirm("Write a description in your editor?", false)?
+    {
+        args.description = input::edit("issue", &Draft::new(None))?
+            .body()
+            .map(str::to_string);
     }
+    summary.push(("Description", prompt::lines(args.description.as_deref())));
 
-    Ok(())
+    prompt::confirm_summary("Create this issue?", &summary)
 }
 
 pub async fn handle_update(client: &LinearClient, args: &UpdateArgs) -> Result<()> {
-    let mut input = serde_json::Map::new();
+    if !args.updates_fields() && args.add_labels.is_empty() && args.remove_labels.is_empty() {
+        println!(
+            "No updates specified. See `linear issue update --help` for the fields you can change."
+        );
+        return Ok(());
+    }
 
-    if let Some(title) = &args.title {
-        input.insert("title".to_string(), serde_json::json!(title));
+    let before = client
+        .run(IssueFieldsQuery::build(IdVariables {
+            id: args.id.clone(),
+        }))
+        .await?
+        .issue;
+    let id = before.id.inner().to_string();
+
+    // States, labels and cycles are looked up in the team the issue ends up in
+    let team = match &args.team {
+        Some(team) => {
+            let team = resolve::team(client, team).await?;
+            TeamRef {
+                id: team.id,
+                key: team.key,
+            }
+        }
+        None => TeamRef {
+            id: before.team.id.clone(),
+            key: before.team.key.clone(),
+        },
+    };
+    let team_id = team.id.inner();
+
+    let mut title = args.title.clone();
+    let mut description = input::text(
+        args.description.as_deref(),
+        args.description_file.as_deref(),
+    )?;
+    if args.editor {
+        let draft = Draft::new(description.as_deref().or(before.description.as_deref()))
+            .field("title", Some(title.as_deref().unwrap_or(&before.title)));
+        let draft = input::edit(&before.identifier, &draft)?;
+        let edited = draft
+            .get("title")
+            .ok_or_else(|| Error::InvalidInput("the title is empty".to_string()))?;
+        title = (edited != before.title).then(|| edited.to_string());
+        let edited = draft.body.clone();
+        description =
+            (edited != before.description.as_deref().unwrap_or_default().trim()).then_some(edited);
     }
+
+    let mut input = IssueUpdateInput {
+        title,
+        description,
+        priority: args.priority,
+        team_id: args.team.is_some().then(|| team_id.to_string()),
+        ..Default::default()
+    };
     if let Some(state) = &args.state {
-        input.insert("stateId".to_string(), serde_json::json!(state));
+        input.state_id = Some(resolve::state(client, &team, state).await?);
     }
-    if let Some(priority) = args.priority {
-        input.insert("priority".to_string(), serde_json::json!(priority));
+    if let Some(assignee) = &args.assignee {
+        input.assignee_id = if assignee.eq_ignore_ascii_case("none") {
+            Patch::Clear
+        } else {
+            Patch::Set(resolve::user(client, assignee).await?)
+        };
+    }
+    if let Some(project) = &args.project {
+        input.project_id = Some(resolve::project(client, project).await?);
+    }
+    if args.cycle.is_some() || args.estimate.is_some() {
+        let settings = resolve::team_settings(client, team_id).await?;
+        if let Some(estimate) = &args.estimate {
+            input.estimate = Some(resolve::estimate(&settings, estimate)?);
+        }
+        if let Some(cycle) = &args.cycle {
+            input.cycle_id = Some(resolve::cycle(client, &settings, cycle).await?);
+        }
+    }
+    if let Some(due) = &args.due {
+        input.due_date = Patch::Set(due_date(due)?);
+    } else if args.clear_due {
+        input.due_date = Patch::Clear;
+    }
+    if let Some(parent) = &args.parent {
+        input.parent_id = Patch::Set(resolve::issue(client, parent).await?.id.into_inner());
+    } else if args.no_parent {
+        input.parent_id = Patch::Clear;
+    }
+    let mut added = Vec::new();
+    if !args.add_labels.is_empty() {
+        added = resolve::labels(client, team_id, &args.add_labels).await?;
+    }
+    let mut removed = Vec::new();
+    if !args.remove_labels.is_empty() {
+        removed = resolve::labels(client, team_id, &args.remove_labels).await?;
     }
 
-    if input.is_empty() {
-        println!("No updates specified. Use --title, --state, or --priority.");
-        return Ok(());
+    let mut after = None;
+    if args.updates_fields() {
+        let operation = UpdateIssueMutation::build(UpdateIssueVariables {
+            id: id.clone(),
+            input,
+        });
+        after = Some(updated(client.run(operation).await?.issue_update)?);
+    }
+    for label_id in added {
+        let operation = AddLabelMutation::build(IssueLabelVariables {
+            id: id.clone(),
+            label_id,
+        });
+        after = Some(updated(client.run(operation).await?.issue_add_label)?);
+    }
+    for label_id in removed {
+        let operation = RemoveLabelMutation::build(IssueLabelVariables {
+            id: id.clone(),
+            label_id,
+        });
+        after = Some(updated(client.run(operation).await?.issue_remove_label)?);
     }
 
-    let variables = serde_json::json!({
-        "id": args.id,
-        "input": input
+    let after = after.expect("at least one mutation was sent");
+    output::print_issue_changes(&IssueChanges {
+        changes: changes(&before, &after),
+        identifier: after.identifier,
+        title: after.title,
     });
+    Ok(())
+}
 
-    let response: UpdateIssueResponse = client.query(UPDATE_ISSUE_MUTATION, variables).await?;
-
-    if response.issue_update.success {
-        if let Some(issue) = response.issue_update.issue {
-            let state_name = issue
-                .state
-                .map(|s| s.name)
-                .unwrap_or_else(|| "—".to_string());
-            println!(
-                "Updated {} - {} [{}]",
-                issue.identifier, issue.title, state_name
-            );
-        }
-    } else {
-        return Err(crate::error::Error::GraphQL(
-            "Failed to update issue".to_string(),
-        ));
+fn updated(payload: IssueUpdatePayload) -> Result<UpdatedIssue> {
+    match payload.issue {
+        Some(issue) if payload.success => Ok(issue),
+        _ => Err(Error::GraphQL("Failed to update issue".to_string())),
     }
+}
 
-    Ok(())
+/// The fields that differ between two reads of an issue.
+fn changes(before: &UpdatedIssue, after: &UpdatedIssue) -> Vec<FieldChange> {
+    fields(before)
+        .into_iter()
+        .zip(fields(after))
+        .filter(|((_, before), (_, after))| before != after)
+        .map(|((field, before), (_, after))| FieldChange {
+            field,
+            before,
+            after,
+        })
+        .collect()
 }
 
-fn build_filter(args: &ListArgs) -> serde_json::Value {
-    let mut filter = serde_json::Map::new();
+fn fields(issue: &UpdatedIssue) -> Vec<(&'static str, Option<String>)> {
+    let mut labels: Vec<&str> = issue.labels.nodes.iter().map(|l| l.name.as_str()).collect();
+    labels.sort_unstable();
+    vec![
+        ("team", Some(issue.team.key.clone())),
+        ("title", Some(issue.title.clone())),
+        (
+            "description",
+            issue.description.clone().filter(|d| !d.is_empty()),
+        ),
+        ("state", issue.state.as_ref().map(|s| s.name.clone())),
+        ("assignee", issue.assignee.as_ref().map(|a| a.name.clone())),
+        ("priority", Some(output::priority_label(issue.priority))),
+        ("labels", (!labels.is_empty()).then(|| labels.join(", "))),
+        ("project", issue.project.as_ref().map(|p| p.name.clone())),
+        (
+            "cycle",
+            issue.cycle.as_ref().map(|c| match &c.name {
+                Some(name) => format!("{} ({})", c.number, name),
+                None => c.number.to_string(),
+            }),
+        ),
+        ("estimate", issue.estimate.map(|e| e.to_string())),
+        ("due", issue.due_date.clone()),
+        (
+            "parent",
+            issue.parent.as_ref().map(|p| p.identifier.clone()),
+        ),
+    ]
+}
 
-    if let Some(team) = &args.team {
-        filter.insert(
-            "team".to_string(),
-            serde_json::json!({ "key": { "eq": team } }),
-        );
-    }
-    if let Some(state) = &args.state {
-        filter.insert(
-            "state".to_string(),
-            serde_json::json!({ "name": { "eq": state } }),
-        );
-    }
-    if let Some(assignee) = &args.assignee {
-        if assignee == "me" {
-            filter.insert(
-                "assignee".to_string(),
-                serde_json::json!({ "isMe": { "eq": true } }),
-            );
-        } else {
-            filter.insert(
-                "assignee".to_string(),
-                serde_json::json!({ "name": { "contains": assignee } }),
-            );
+/// Check a due date is a calendar date written as YYYY-MM-DD.
+fn due_date(input: &str) -> Result<String> {
+    let parts: Vec<&str> = input.split('-').collect();
+    let valid = match parts.as_slice() {
+        [year, month, day] if year.len() == 4 && month.len() == 2 && day.len() == 2 => {
+            match (
+                year.parse::<u32>(),
+                month.parse::<u32>(),
+                day.parse::<u32>(),
+            ) {
+                (Ok(year), Ok(month), Ok(day)) => {
+                    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+                    let days = match month {
+                        2 if leap => 29,
+                        2 => 28,
+                        4 | 6 | 9 | 11 => 30,
+                        1..=12 => 31,
+                        _ => 0,
+                    };
+                    (1..=days).contains(&day)
+                }
+                _ => false,
+            }
         }
+        _ => false,
+    };
+    if valid {
+        Ok(input.to_string())
+    } else {
+        Err(Error::InvalidInput(format!(
+            "invalid due date '{}', expected YYYY-MM-DD",
+            input
+        )))
     }
+}
 
-    serde_json::Value::Object(filter)
+/// Build the issue filter from the list flags, falling back to the
+/// profile's `issue_list` defaults for any flag that wasn't given.
+fn build_filter(args: &ListArgs, defaults: &IssueListDefaults) -> IssueFilter {
+    let team = args.team.as_ref().or(defaults.team.as_ref());
+    let state = args.state.as_ref().or(defaults.state.as_ref());
+    let assignee = args.assignee.as_ref().or(defaults.assignee.as_ref());
+
+    IssueFilter {
+        team: team.map(|key| TeamFilter::key(key)),
+        state: state.map(|name| WorkflowStateFilter {
+            name: StringComparator::eq(name),
+        }),
+        assignee: assignee.map(|assignee| {
+            if assignee == "me" {
+                NullableUserFilter {
+                    is_me: Some(BooleanComparator { eq: true }),
+                    ..Default::default()
+                }
+            } else {
+                NullableUserFilter {
+                    name: Some(StringComparator::contains(assignee)),
+                    ..Default::default()
+                }
+            }
+        }),
+        ..Default::default()
+    }
 }
@@ -1,9 +1,11 @@
 use clap::{Args, Subcommand};
-use serde::Deserialize;
+use clap_complete::ArgValueCandidates;
 
 use crate::client::LinearClient;
+use crate::complete;
 use crate::error::Result;
 use crate::output;
+use crate::resolve;
 
 #[derive(Subcommand)]
 pub enum LabelCommands {
@@ -14,53 +16,19 @@ pub enum LabelCommands {
 #[derive(Args)]
 pub struct ListLabelArgs {
     /// Filter by team key
-    #[arg(long)]
+    #[arg(long, add = ArgValueCandidates::new(complete::teams))]
     pub team: Option<String>,
 }
 
-#[derive(Deserialize)]
-struct LabelsResponse {
-    #[serde(rename = "issueLabels")]
-    issue_labels: LabelsConnection,
-}
-
-#[derive(Deserialize)]
-struct LabelsConnection {
-    nodes: Vec<Label>,
-}
-
-#[derive(Deserialize, Clone)]
-pub struct Label {
-    #[allow(dead_code)]
-    pub id: String,
-    pub name: String,
-    #[allow(dead_code)]
-    pub color: String,
-}
-
-const LABELS_QUERY: &str = r#"
-    query Labels($filter: IssueLabelFilter) {
-        issueLabels(filter: $filter) {
-            nodes {
-                id
-                name
-                color
-            }
-        }
-    }
-"#;
-
 pub async fn handle_list(client: &LinearClient, args: &ListLabelArgs) -> Result<()> {
-    let mut filter = serde_json::Map::new();
-    if let Some(team) = &args.team {
-        filter.insert(
-            "team".to_string(),
-            serde_json::json!({ "key": { "eq": team } }),
-        );
+    let mut labels = resolve::all_labels(client).await?;
+    if let Some(key) = &args.team {
+        labels.retain(|l| {
+            l.team
+                .as_ref()
+                .is_some_and(|t| t.key.eq_ignore_ascii_case(key))
+        });
     }
-
-    let variables = serde_json::json!({ "filter": filter });
-    let response: LabelsResponse = client.query(LABELS_QUERY, variables).await?;
-    output::print_labels(&response.issue_labels.nodes);
+    output::print_labels(&labels);
     Ok(())
 }
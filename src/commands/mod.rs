@@ -1,10 +1,17 @@
+pub mod api;
+pub mod auth;
+pub mod cache;
 pub mod comment;
+pub mod completion;
+pub mod config;
 pub mod cycle;
+pub mod docs;
 pub mod document;
 pub mod issue;
 pub mod label;
 pub mod project;
 pub mod search;
+pub mod sync;
 pub mod team;
 pub mod update;
 pub mod user;
@@ -14,6 +21,11 @@ use clap::Subcommand;
 
 #[derive(Subcommand)]
 pub enum Commands {
+    /// Log in, log out and show the active credential
+    Auth {
+        #[command(subcommand)]
+        command: auth::AuthCommands,
+    },
     /// User operations
     User {
         #[command(subcommand)]
@@ -61,6 +73,24 @@ pub enum Commands {
     },
     /// Search issues
     Search(search::SearchArgs),
-    /// Update to the latest version
-    Update,
+    /// Mirror issues, comments, projects and cycles locally for --offline
+    Sync(sync::SyncArgs),
+    /// Run a GraphQL query or inspect the Linear API
+    Api(api::ApiArgs),
+    /// Inspect or clear the local metadata cache
+    Cache {
+        #[command(subcommand)]
+        command: cache::CacheCommands,
+    },
+    /// Manage configuration and profiles
+    Config {
+        #[command(subcommand)]
+        command: config::ConfigCommands,
+    },
+    /// Print a shell completion script
+    Completion(completion::CompletionArgs),
+    /// Write the command reference as man pages or Markdown
+    Docs(docs::DocsArgs),
+    /// Update to the latest version, or check for, pin or roll back one
+    Update(update::UpdateArgs),
 }
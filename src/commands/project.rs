@@ -1,9 +1,19 @@
 use clap::{Args, Subcommand};
-use serde::Deserialize;
+use clap_complete::ArgValueCandidates;
+use cynic::{MutationBuilder, QueryBuilder};
 
 use crate::client::LinearClient;
+use crate::complete;
+use crate::config::Profile;
 use crate::error::Result;
 use crate::output;
+use crate::queries::project::{
+    CreateProjectMutation, CreateProjectVariables, ProjectCreateInput, ProjectFilter, ProjectQuery,
+    ProjectsQuery, ProjectsVariables, TeamCollectionFilter,
+};
+use crate::queries::{IdVariables, TeamFilter};
+use crate::resolve;
+use crate::store::Store;
 
 #[derive(Subcommand)]
 pub enum ProjectCommands {
@@ -18,17 +28,22 @@ pub enum ProjectCommands {
 #[derive(Args)]
 pub struct ListProjectArgs {
     /// Filter by team key
-    #[arg(long)]
+    #[arg(long, add = ArgValueCandidates::new(complete::teams))]
     pub team: Option<String>,
 
     /// Maximum number to show
     #[arg(short = 'n', long, default_value = "25")]
     pub limit: u32,
+
+    /// Fetch every matching project, ignoring --limit
+    #[arg(long, conflicts_with = "limit")]
+    pub all: bool,
 }
 
 #[derive(Args)]
 pub struct GetProjectArgs {
     /// Project ID or name
+    #[arg(add = ArgValueCandidates::new(complete::projects))]
     pub id: String,
 }
 
@@ -38,161 +53,79 @@ pub struct CreateProjectArgs {
     #[arg(long)]
     pub name: String,
 
-    /// Team key
-    #[arg(long)]
-    pub team: String,
+    /// Team key [default: the profile's default_team]
+    #[arg(long, add = ArgValueCandidates::new(complete::teams))]
+    pub team: Option<String>,
 
     /// Project description
     #[arg(long)]
     pub description: Option<String>,
 }
 
-#[derive(Deserialize)]
-struct ProjectsResponse {
-    projects: ProjectsConnection,
-}
-
-#[derive(Deserialize)]
-struct ProjectsConnection {
-    nodes: Vec<Project>,
-}
-
-#[derive(Deserialize, Clone)]
-#[allow(dead_code)]
-pub struct Project {
-    pub id: String,
-    pub name: String,
-    pub state: String,
-    pub progress: f64,
-    #[serde(rename = "startDate")]
-    pub start_date: Option<String>,
-    #[serde(rename = "targetDate")]
-    pub target_date: Option<String>,
-}
-
-#[derive(Deserialize)]
-struct ProjectResponse {
-    project: ProjectDetail,
-}
-
-#[derive(Deserialize)]
-#[allow(dead_code)]
-pub struct ProjectDetail {
-    pub id: String,
-    pub name: String,
-    pub description: Option<String>,
-    pub state: String,
-    pub progress: f64,
-    #[serde(rename = "startDate")]
-    pub start_date: Option<String>,
-    #[serde(rename = "targetDate")]
-    pub target_date: Option<String>,
-}
-
-#[derive(Deserialize)]
-struct CreateProjectResponse {
-    #[serde(rename = "projectCreate")]
-    project_create: ProjectCreatePayload,
-}
-
-#[derive(Deserialize)]
-struct ProjectCreatePayload {
-    success: bool,
-    project: Option<CreatedProject>,
-}
-
-#[derive(Deserialize)]
-#[allow(dead_code)]
-struct CreatedProject {
-    id: String,
-    name: String,
-    url: String,
-}
-
-const PROJECTS_QUERY: &str = r#"
-    query Projects($first: Int, $filter: ProjectFilter) {
-        projects(first: $first, filter: $filter) {
-            nodes {
-                id
-                name
-                state
-                progress
-                startDate
-                targetDate
-            }
-        }
-    }
-"#;
-
-const PROJECT_QUERY: &str = r#"
-    query Project($id: String!) {
-        project(id: $id) {
-            id
-            name
-            description
-            state
-            progress
-            startDate
-            targetDate
-        }
-    }
-"#;
-
-const CREATE_PROJECT_MUTATION: &str = r#"
-    mutation CreateProject($input: ProjectCreateInput!) {
-        projectCreate(input: $input) {
-            success
-            project {
-                id
-                name
-                url
-            }
-        }
-    }
-"#;
-
 pub async fn handle_list(client: &LinearClient, args: &ListProjectArgs) -> Result<()> {
-    let mut filter = serde_json::Map::new();
-    if let Some(team) = &args.team {
-        filter.insert(
-            "accessibleTeams".to_string(),
-            serde_json::json!({ "key": { "eq": team } }),
-        );
-    }
-
-    let variables = serde_json::json!({
-        "first": args.limit,
-        "filter": filter
-    });
-
-    let response: ProjectsResponse = client.query(PROJECTS_QUERY, variables).await?;
-    output::print_projects(&response.projects.nodes);
-    Ok(())
+    let limit = (!args.all).then_some(args.limit);
+
+    client
+        .paginate_print(
+            limit,
+            |first, after| {
+                ProjectsQuery::build(ProjectsVariables {
+                    first,
+                    after,
+                    filter: ProjectFilter {
+                        accessible_teams: args.team.as_deref().map(|key| TeamCollectionFilter {
+                            some: TeamFilter::key(key),
+                        }),
+                        ..Default::default()
+                    },
+                })
+            },
+            |r| r.projects.into(),
+            output::print_projects,
+        )
+        .await
 }
 
 pub async fn handle_get(client: &LinearClient, args: &GetProjectArgs) -> Result<()> {
-    let variables = serde_json::json!({ "id": args.id });
-    let response: ProjectResponse = client.query(PROJECT_QUERY, variables).await?;
+    // `project(id:)` only takes an ID, so a name is looked up first
+    let id = resolve::project(client, &args.id).await?;
+    let response = client.run(ProjectQuery::build(IdVariables { id })).await?;
     output::print_project_detail(&response.project);
     Ok(())
 }
 
-pub async fn handle_create(client: &LinearClient, args: &CreateProjectArgs) -> Result<()> {
-    let mut input = serde_json::Map::new();
-    input.insert("name".to_string(), serde_json::json!(args.name));
-    input.insert("teamIds".to_string(), serde_json::json!([args.team]));
+pub fn handle_list_offline(store: &Store, args: &ListProjectArgs) -> Result<()> {
+    let limit = (!args.all).then_some(args.limit);
+    output::print_projects(&store.projects(args.team.as_deref(), limit)?);
+    Ok(())
+}
 
-    if let Some(desc) = &args.description {
-        input.insert("description".to_string(), serde_json::json!(desc));
-    }
+pub fn handle_get_offline(store: &Store, args: &GetProjectArgs) -> Result<()> {
+    output::print_project_detail(&store.project(&args.id)?);
+    Ok(())
+}
 
-    let variables = serde_json::json!({ "input": input });
-    let response: CreateProjectResponse = client.query(CREATE_PROJECT_MUTATION, variables).await?;
+pub async fn handle_create(
+    client: &LinearClient,
+    args: &CreateProjectArgs,
+    profile: &Profile,
+) -> Result<()> {
+    let team = resolve::team(client, profile.team(args.team.as_deref())?).await?;
+
+    let operation = CreateProjectMutation::build(CreateProjectVariables {
+        input: ProjectCreateInput {
+            name: args.name.clone(),
+            team_ids: vec![team.id.into_inner()],
+            description: args.description.clone(),
+        },
+    });
+    let response = client.run(operation).await?;
+    // The new project should be resolvable by name straight away
+    client.cache().invalidate("projects");
 
     if response.project_create.success {
         if let Some(project) = response.project_create.project {
-            println!("Created project: {}", project.name);
-            println!("{}", project.url);
+            output::print_created_project(&project);
         }
     } else {
         return Err(crate::error::Error::GraphQL(
@@ -1,9 +1,11 @@
 use clap::Args;
-use serde::Deserialize;
+use cynic::QueryBuilder;
 
 use crate::client::LinearClient;
 use crate::error::Result;
 use crate::output;
+use crate::queries::search::{SearchIssuesQuery, SearchVariables};
+use crate::store::Store;
 
 #[derive(Args)]
 pub struct SearchArgs {
@@ -13,55 +15,33 @@ pub struct SearchArgs {
     /// Maximum results
     #[arg(short = 'n', long, default_value = "10")]
     pub limit: u32,
-}
-
-#[derive(Deserialize)]
-struct SearchResponse {
-    #[serde(rename = "searchIssues")]
-    search_issues: SearchConnection,
-}
-
-#[derive(Deserialize)]
-struct SearchConnection {
-    nodes: Vec<SearchResult>,
-}
 
-#[derive(Deserialize, Clone)]
-pub struct SearchResult {
-    #[allow(dead_code)]
-    pub id: String,
-    pub identifier: String,
-    pub title: String,
-    pub state: Option<SearchState>,
+    /// Fetch every matching result, ignoring --limit
+    #[arg(long, conflicts_with = "limit")]
+    pub all: bool,
 }
 
-#[derive(Deserialize, Clone)]
-pub struct SearchState {
-    pub name: String,
-}
-
-const SEARCH_QUERY: &str = r#"
-    query SearchIssues($query: String!, $first: Int) {
-        searchIssues(query: $query, first: $first) {
-            nodes {
-                id
-                identifier
-                title
-                state {
-                    name
-                }
-            }
-        }
-    }
-"#;
-
 pub async fn handle_search(client: &LinearClient, args: &SearchArgs) -> Result<()> {
-    let variables = serde_json::json!({
-        "query": args.query,
-        "first": args.limit
-    });
+    let limit = (!args.all).then_some(args.limit);
+
+    client
+        .paginate_print(
+            limit,
+            |first, after| {
+                SearchIssuesQuery::build(SearchVariables {
+                    term: args.query.clone(),
+                    first,
+                    after,
+                })
+            },
+            |r| r.search_issues.into(),
+            output::print_search_results,
+        )
+        .await
+}
 
-    let response: SearchResponse = client.query(SEARCH_QUERY, variables).await?;
-    output::print_search_results(&response.search_issues.nodes);
+pub fn handle_search_offline(store: &Store, args: &SearchArgs) -> Result<()> {
+    let limit = (!args.all).then_some(args.limit);
+    output::print_search_results(&store.search(&args.query, limit)?);
     Ok(())
 }
@@ -0,0 +1,193 @@
+use clap::Args;
+use clap_complete::ArgValueCandidates;
+use cynic::QueryBuilder;
+use serde::Deserialize;
+
+use crate::client::{Connection, LinearClient};
+use crate::complete;
+use crate::config::Profile;
+use crate::error::Result;
+use crate::output;
+use crate::queries::cycle::{CycleFilter, CyclesVariables};
+use crate::queries::issue::{IssueFilter, IssuesVariables};
+use crate::queries::project::{ProjectFilter, ProjectsVariables, TeamCollectionFilter};
+use crate::queries::sync::{
+    CommentFilter, NullableIssueFilter, SyncCommentsQuery, SyncCommentsVariables, SyncCyclesQuery,
+    SyncIssuesQuery, SyncProjectsQuery,
+};
+use crate::queries::user::ViewerIdQuery;
+use crate::queries::{DateComparator, TeamFilter};
+use crate::store::{self, Record, Store, SyncSummary};
+
+/// Records fetched per request; projects select their teams too, so this
+/// stays well under the complexity limit.
+const PAGE_SIZE: i32 = 100;
+
+#[derive(Args)]
+pub struct SyncArgs {
+    /// Only sync this team's data (e.g., ENG)
+    #[arg(long, add = ArgValueCandidates::new(complete::teams))]
+    pub team: Option<String>,
+}
+
+pub async fn handle_sync(client: &LinearClient, args: &SyncArgs, profile: &Profile) -> Result<()> {
+    let mut store = Store::open(&profile.name)?;
+    let team = args.team.as_deref().map(str::to_uppercase);
+    let team = team.as_deref();
+    let scope = team.unwrap_or("*");
+
+    // Lets `issue list --assignee me` work offline
+    let viewer = client.run(ViewerIdQuery::build(())).await?;
+    store.set_meta("viewer_id", viewer.viewer.id.inner())?;
+
+    let mut summary = Vec::new();
+    summary.push(
+        sync(
+            client,
+            &mut store,
+            scope,
+            |first, after, since| {
+                SyncIssuesQuery::build(IssuesVariables {
+                    first,
+                    after,
+                    filter: IssueFilter {
+                        team: team.map(TeamFilter::key),
+                        updated_at: since.map(|gte| DateComparator { gte }),
+                        ..Default::default()
+                    },
+                })
+            },
+            |r| r.issues.into(),
+        )
+        .await?,
+    );
+    summary.push(
+        sync(
+            client,
+            &mut store,
+            scope,
+            |first, after, since| {
+                SyncCommentsQuery::build(SyncCommentsVariables {
+                    first,
+                    after,
+                    filter: CommentFilter {
+                        issue: team.map(|key| NullableIssueFilter {
+                            team: Some(TeamFilter::key(key)),
+                        }),
+                        updated_at: since.map(|gte| DateComparator { gte }),
+                    },
+                })
+            },
+            |r| r.comments.into(),
+        )
+        .await?,
+    );
+    summary.push(
+        sync(
+            client,
+            &mut store,
+            scope,
+            |first, after, since| {
+                SyncProjectsQuery::build(ProjectsVariables {
+                    first,
+                    after,
+                    filter: ProjectFilter {
+                        accessible_teams: team.map(|key| TeamCollectionFilter {
+                            some: TeamFilter::key(key),
+                        }),
+                        updated_at: since.map(|gte| DateComparator { gte }),
+                        ..Default::default()
+                    },
+                })
+            },
+            |r| r.projects.into(),
+        )
+        .await?,
+    );
+    summary.push(
+        sync(
+            client,
+            &mut store,
+            scope,
+            |first, after, since| {
+                SyncCyclesQuery::build(CyclesVariables {
+                    first,
+                    after,
+                    filter: CycleFilter {
+                        team: team.map(TeamFilter::key),
+                        updated_at: since.map(|gte| DateComparator { gte }),
+                        ..Default::default()
+                    },
+                })
+            },
+            |r| r.cycles.into(),
+        )
+        .await?,
+    );
+
+    output::print_sync_summary(&store::path(&profile.name)?, &summary);
+    Ok(())
+}
+
+/// Pull every record of one entity type updated since its high-water mark,
+/// saving each page as it arrives.
+///
+/// `build` constructs the operation for a page size, cursor and `updatedAt`
+/// lower bound. If a previous run was interrupted, this one resumes from its
+/// cursor with the same lower bound, and the high-water mark only advances
+/// once the last page is stored.
+async fn sync<Q, V, T: Record>(
+    client: &LinearClient,
+    store: &mut Store,
+    scope: &str,
+    build: impl Fn(i32, Option<String>, Option<String>) -> cynic::Operation<Q, V>,
+    extract: impl Fn(Q) -> Connection<T>,
+) -> Result<SyncSummary>
+where
+    Q: for<'de> Deserialize<'de>,
+    V: serde::Serialize,
+{
+    let mut state = store.sync_state(T::ENTITY, scope)?;
+    if state.cursor.is_none() {
+        state.since = state.high_water.clone();
+        state.max_seen = state.high_water.clone();
+    }
+
+    let mut synced = 0;
+    loop {
+        let response = client
+            .run(build(PAGE_SIZE, state.cursor.clone(), state.since.clone()))
+            .await?;
+        let connection = extract(response);
+
+        for record in &connection.nodes {
+            // RFC 3339 timestamps from the API compare correctly as strings
+            if state.max_seen.as_deref() < Some(record.updated_at()) {
+                state.max_seen = Some(record.updated_at().to_string());
+            }
+        }
+        let done = match connection.page_info.end_cursor {
+            Some(cursor) if connection.page_info.has_next_page && !connection.nodes.is_empty() => {
+                state.cursor = Some(cursor);
+                false
+            }
+            _ => {
+                state.cursor = None;
+                state.since = None;
+                state.high_water = state.max_seen.clone();
+                true
+            }
+        };
+        store.save_page(scope, &connection.nodes, &state)?;
+        synced += connection.nodes.len();
+
+        if done {
+            return Ok(SyncSummary {
+                entity: T::ENTITY,
+                synced,
+                high_water: state.high_water,
+            });
+        }
+        client.throttle().await;
+    }
+}
@@ -1,9 +1,14 @@
 use clap::{Args, Subcommand};
-use serde::Deserialize;
+use clap_complete::ArgValueCandidates;
+use cynic::QueryBuilder;
 
 use crate::client::LinearClient;
+use crate::complete;
 use crate::error::Result;
 use crate::output;
+use crate::queries::team::TeamQuery;
+use crate::queries::IdVariables;
+use crate::resolve;
 
 #[derive(Subcommand)]
 pub enum TeamCommands {
@@ -16,66 +21,23 @@ pub enum TeamCommands {
 #[derive(Args)]
 pub struct GetTeamArgs {
     /// Team key or ID (e.g., ENG)
+    #[arg(add = ArgValueCandidates::new(complete::teams))]
     pub key: String,
 }
 
-#[derive(Deserialize)]
-struct TeamsResponse {
-    teams: TeamsConnection,
-}
-
-#[derive(Deserialize)]
-struct TeamsConnection {
-    nodes: Vec<Team>,
-}
-
-#[derive(Deserialize, Clone)]
-#[allow(dead_code)]
-pub struct Team {
-    pub id: String,
-    pub key: String,
-    pub name: String,
-    pub description: Option<String>,
-}
-
-#[derive(Deserialize)]
-struct TeamResponse {
-    team: Team,
-}
-
-const TEAMS_QUERY: &str = r#"
-    query Teams {
-        teams {
-            nodes {
-                id
-                key
-                name
-                description
-            }
-        }
-    }
-"#;
-
-const TEAM_QUERY: &str = r#"
-    query Team($id: String!) {
-        team(id: $id) {
-            id
-            key
-            name
-            description
-        }
-    }
-"#;
-
 pub async fn handle_list(client: &LinearClient) -> Result<()> {
-    let response: TeamsResponse = client.query(TEAMS_QUERY, serde_json::json!({})).await?;
-    output::print_teams(&response.teams.nodes);
+    let teams = resolve::teams(client).await?;
+    output::print_teams(&teams);
     Ok(())
 }
 
 pub async fn handle_get(client: &LinearClient, args: &GetTeamArgs) -> Result<()> {
-    let variables = serde_json::json!({ "id": args.key });
-    let response: TeamResponse = client.query(TEAM_QUERY, variables).await?;
+    let team = resolve::team(client, &args.key).await?;
+    let response = client
+        .run(TeamQuery::build(IdVariables {
+            id: team.id.into_inner(),
+        }))
+        .await?;
     output::print_team_detail(&response.team);
     Ok(())
 }
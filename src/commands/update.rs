@@ -1,17 +1,78 @@
+//! `linear update`: replace the running binary with a GitHub release.
+//!
+//! Every download is checked against the `<asset>.sha256` file published
+//! with the release, and builds that embed a minisign public key also
+//! require a valid `<asset>.minisig` signature. The replaced binary is kept
+//! next to the new one so `--rollback` can restore it.
+
 use std::env;
 use std::fs;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
 
+use clap::Args;
+use semver::Version;
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 
 use crate::error::{Error, Result};
 
 const GITHUB_REPO: &str = "nickcramaro/linear-cli";
-const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const GITHUB_API_URL: &str = "https://api.github.com";
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Minisign public key release assets are signed with, set at build time.
+/// Builds without one only verify checksums.
+const PUBLIC_KEY: Option<&str> = option_env!("LINEAR_UPDATE_PUBLIC_KEY");
+
+/// Exit code of `linear update --check` when a newer release exists.
+pub const UPDATE_AVAILABLE: i32 = 10;
+
+/// How `linear update` finished, which decides its exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Up to date, updated or rolled back
+    Done,
+    /// `--check` found a newer release
+    UpdateAvailable,
+}
+
+impl Outcome {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Outcome::Done => 0,
+            Outcome::UpdateAvailable => UPDATE_AVAILABLE,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct UpdateArgs {
+    /// Only report whether a newer release exists, exiting with code 10 if so
+    #[arg(long, conflicts_with = "rollback")]
+    pub check: bool,
+
+    /// Install this release instead of the latest (e.g., v0.3.0); allows
+    /// downgrades
+    #[arg(long, value_name = "TAG", conflicts_with = "rollback")]
+    pub version: Option<String>,
+
+    /// Consider prereleases when looking for the latest release
+    #[arg(long)]
+    pub prerelease: bool,
+
+    /// Restore the binary replaced by the last update
+    #[arg(long)]
+    pub rollback: bool,
+}
 
 #[derive(Deserialize)]
-struct Release {
-    tag_name: String,
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
     assets: Vec<Asset>,
 }
 
@@ -21,6 +82,27 @@ struct Asset {
     browser_download_url: String,
 }
 
+impl Release {
+    pub fn version(&self) -> Option<Version> {
+        Version::parse(self.tag_name.trim_start_matches('v')).ok()
+    }
+
+    /// Whether this release is newer than the running binary.
+    pub fn is_newer(&self) -> bool {
+        is_newer(&self.tag_name)
+    }
+
+    fn asset(&self, name: &str) -> Option<&Asset> {
+        self.assets.iter().find(|a| a.name == name)
+    }
+}
+
+/// Whether the release tagged `tag` is newer than the running binary.
+pub fn is_newer(tag: &str) -> bool {
+    let current = Version::parse(CURRENT_VERSION).expect("package version is semver");
+    Version::parse(tag.trim_start_matches('v')).is_ok_and(|v| v > current)
+}
+
 fn get_asset_name() -> Result<&'static str> {
     let os = env::consts::OS;
     let arch = env::consts::ARCH;
@@ -30,82 +112,323 @@ fn get_asset_name() -> Result<&'static str> {
         ("macos", "x86_64") => Ok("linear-macos-x86_64"),
         ("linux", "x86_64") => Ok("linear-linux-x86_64"),
         ("linux", "aarch64") => Ok("linear-linux-aarch64"),
-        _ => Err(Error::GraphQL(format!(
-            "Unsupported platform: {}-{}",
+        ("windows", "x86_64") => Ok("linear-windows-x86_64.exe"),
+        _ => Err(Error::Update(format!(
+            "unsupported platform: {}-{}",
             os, arch
         ))),
     }
 }
 
-pub async fn handle_update() -> Result<()> {
-    println!("Current version: v{}", CURRENT_VERSION);
-    println!("Checking for updates...");
+/// The GitHub API: `LINEAR_GITHUB_API_URL`, or api.github.com.
+fn api_url() -> String {
+    env::var("LINEAR_GITHUB_API_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| GITHUB_API_URL.to_string())
+}
 
-    // Fetch latest release from GitHub
-    let client = reqwest::Client::new();
-    let release: Release = client
-        .get(format!(
-            "https://api.github.com/repos/{}/releases/latest",
-            GITHUB_REPO
-        ))
-        .header("User-Agent", "linear-cli")
-        .send()
+fn http() -> reqwest::Client {
+    reqwest::Client::builder()
+        .user_agent(concat!("linear-cli/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .expect("failed to build http client")
+}
+
+async fn get(client: &reqwest::Client, url: &str) -> Result<reqwest::Response> {
+    check(client.get(url).send().await?)
+}
+
+fn check(response: reqwest::Response) -> Result<reqwest::Response> {
+    let status = response.status();
+    if !status.is_success() {
+        return Err(Error::Update(format!(
+            "GitHub returned {} for {}",
+            status,
+            response.url()
+        )));
+    }
+    Ok(response)
+}
+
+/// The newest published release, including prereleases if asked to.
+pub async fn latest_release(prerelease: bool) -> Result<Release> {
+    let client = http();
+    let base = format!("{}/repos/{}/releases", api_url(), GITHUB_REPO);
+    if !prerelease {
+        return Ok(get(&client, &format!("{}/latest", base))
+            .await?
+            .json()
+            .await?);
+    }
+
+    let releases: Vec<Release> = get(&client, &format!("{}?per_page=30", base))
         .await?
         .json()
         .await?;
+    releases
+        .into_iter()
+        .filter(|r| !r.draft)
+        .filter_map(|r| Some((r.version()?, r)))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, r)| r)
+        .ok_or_else(|| Error::Update("no releases published yet".to_string()))
+}
+
+async fn tagged_release(tag: &str) -> Result<Release> {
+    let tag = format!("v{}", tag.trim_start_matches('v'));
+    let url = format!("{}/repos/{}/releases/tags/{}", api_url(), GITHUB_REPO, tag);
+    let response = http().get(&url).send().await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(Error::Update(format!("no release tagged {}", tag)));
+    }
+    Ok(check(response)?.json().await?)
+}
 
-    let latest_version = release.tag_name.trim_start_matches('v');
-    println!("Latest version: v{}", latest_version);
+pub async fn handle_update(args: &UpdateArgs) -> Result<Outcome> {
+    if args.rollback {
+        rollback()?;
+        return Ok(Outcome::Done);
+    }
 
-    if latest_version == CURRENT_VERSION {
+    println!("Current version: v{}", CURRENT_VERSION);
+    println!("Checking for updates...");
+
+    let release = match &args.version {
+        Some(tag) => tagged_release(tag).await?,
+        None => latest_release(args.prerelease).await?,
+    };
+    let version = release.tag_name.trim_start_matches('v');
+    println!(
+        "{} version: v{}{}",
+        if args.version.is_some() {
+            "Requested"
+        } else {
+            "Latest"
+        },
+        version,
+        if release.prerelease {
+            " (prerelease)"
+        } else {
+            ""
+        }
+    );
+
+    if args.check {
+        if release.is_newer() {
+            println!(
+                "Update available: run `linear update{}` to install it",
+                if args.prerelease { " --prerelease" } else { "" }
+            );
+            return Ok(Outcome::UpdateAvailable);
+        }
+        println!("Already up to date!");
+        return Ok(Outcome::Done);
+    }
+    // A pinned version may be older than this one; the latest never is
+    let up_to_date = match args.version {
+        Some(_) => version == CURRENT_VERSION,
+        None => !release.is_newer(),
+    };
+    if up_to_date {
         println!("Already up to date!");
-        return Ok(());
+        return Ok(Outcome::Done);
     }
 
     // Find the right asset for this platform
     let asset_name = get_asset_name()?;
     let asset = release
-        .assets
-        .iter()
-        .find(|a| a.name == asset_name)
-        .ok_or_else(|| Error::GraphQL(format!("No release asset found for {}", asset_name)))?;
-
-    println!("Downloading {}...", asset_name);
-
-    // Download the binary
-    let binary_data = client
-        .get(&asset.browser_download_url)
-        .header("User-Agent", "linear-cli")
-        .send()
+        .asset(asset_name)
+        .ok_or_else(|| Error::Update(format!("no release asset found for {}", asset_name)))?;
+    let checksum = release
+        .asset(&format!("{}.sha256", asset_name))
+        .ok_or_else(|| {
+            Error::Update(format!(
+                "v{} publishes no checksum for {}; refusing to install it",
+                version, asset_name
+            ))
+        })?;
+
+    let client = http();
+    let binary_data = download(&client, asset).await?;
+    verify_checksum(&client, checksum, &binary_data).await?;
+    if let Some(key) = PUBLIC_KEY {
+        let signature = release
+            .asset(&format!("{}.minisig", asset_name))
+            .ok_or_else(|| {
+                Error::Update(format!(
+                    "v{} publishes no signature for {}; refusing to install it",
+                    version, asset_name
+                ))
+            })?;
+        verify_signature(&client, key, signature, &binary_data).await?;
+    }
+
+    install(&binary_data)?;
+    println!("Updated to v{}!", version);
+    println!(
+        "Run `linear update --rollback` to go back to v{}",
+        CURRENT_VERSION
+    );
+
+    Ok(Outcome::Done)
+}
+
+/// Download an asset, showing progress on a terminal.
+async fn download(client: &reqwest::Client, asset: &Asset) -> Result<Vec<u8>> {
+    println!("Downloading {}...", asset.name);
+
+    let mut response = get(client, &asset.browser_download_url).await?;
+    let total = response.content_length();
+    let progress = std::io::stderr().is_terminal();
+
+    let mut data = Vec::with_capacity(total.unwrap_or_default() as usize);
+    while let Some(chunk) = response.chunk().await? {
+        data.extend_from_slice(&chunk);
+        if progress {
+            match total {
+                Some(total) if total > 0 => eprint!(
+                    "\r  {:.1} / {:.1} MB ({}%)",
+                    megabytes(data.len() as u64),
+                    megabytes(total),
+                    data.len() as u64 * 100 / total
+                ),
+                _ => eprint!("\r  {:.1} MB", megabytes(data.len() as u64)),
+            }
+        }
+    }
+    if progress {
+        eprintln!();
+    }
+    Ok(data)
+}
+
+fn megabytes(bytes: u64) -> f64 {
+    bytes as f64 / 1_000_000.0
+}
+
+/// Compare the download with a `sha256sum`-style checksum file.
+async fn verify_checksum(client: &reqwest::Client, checksum: &Asset, data: &[u8]) -> Result<()> {
+    let contents = get(client, &checksum.browser_download_url)
         .await?
-        .bytes()
+        .text()
         .await?;
+    let expected = contents
+        .split_whitespace()
+        .next()
+        .ok_or_else(|| Error::Update(format!("{} is empty", checksum.name)))?;
+    let actual = format!("{:x}", Sha256::digest(data));
 
-    // Get current executable path
-    let current_exe = env::current_exe().map_err(|e| Error::GraphQL(e.to_string()))?;
+    if !expected.eq_ignore_ascii_case(&actual) {
+        return Err(Error::Update(format!(
+            "checksum mismatch: expected {}, downloaded {}; the binary was not replaced",
+            expected, actual
+        )));
+    }
+    println!("Checksum verified");
+    Ok(())
+}
+
+async fn verify_signature(
+    client: &reqwest::Client,
+    key: &str,
+    signature: &Asset,
+    data: &[u8],
+) -> Result<()> {
+    let key = minisign_verify::PublicKey::from_base64(key)
+        .map_err(|e| Error::Update(format!("invalid embedded public key: {}", e)))?;
+    let contents = get(client, &signature.browser_download_url)
+        .await?
+        .text()
+        .await?;
+    let signature = minisign_verify::Signature::decode(&contents)
+        .map_err(|e| Error::Update(format!("invalid {}: {}", signature.name, e)))?;
+    key.verify(data, &signature, false).map_err(|e| {
+        Error::Update(format!(
+            "signature verification failed: {}; the binary was not replaced",
+            e
+        ))
+    })?;
+    println!("Signature verified");
+    Ok(())
+}
+
+/// Where the binary replaced by the last update is kept.
+fn backup_path(exe: &Path) -> PathBuf {
+    exe.with_extension("old")
+}
+
+/// Swap `binary_data` in for the running executable, keeping the current one
+/// as a backup.
+fn install(binary_data: &[u8]) -> Result<()> {
+    let current_exe =
+        env::current_exe().map_err(|e| Error::io("failed to locate the running executable", e))?;
+    let writing = |e| write_error(&current_exe, e);
 
     // Write to a temp file first
     let temp_path = current_exe.with_extension("new");
-    let mut temp_file = fs::File::create(&temp_path).map_err(|e| Error::GraphQL(e.to_string()))?;
-    temp_file
-        .write_all(&binary_data)
-        .map_err(|e| Error::GraphQL(e.to_string()))?;
+    let mut temp_file = fs::File::create(&temp_path).map_err(writing)?;
+    temp_file.write_all(binary_data).map_err(writing)?;
+    drop(temp_file);
 
     // Set executable permissions (Unix only)
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
-        let mut perms = fs::metadata(&temp_path)
-            .map_err(|e| Error::GraphQL(e.to_string()))?
-            .permissions();
+        let mut perms = fs::metadata(&temp_path).map_err(writing)?.permissions();
         perms.set_mode(0o755);
-        fs::set_permissions(&temp_path, perms).map_err(|e| Error::GraphQL(e.to_string()))?;
+        fs::set_permissions(&temp_path, perms).map_err(writing)?;
+    }
+
+    // Keep the current binary, then move the new one into place, restoring
+    // the old one if that fails
+    let backup = backup_path(&current_exe);
+    fs::rename(&current_exe, &backup).map_err(writing)?;
+    if let Err(e) = fs::rename(&temp_path, &current_exe) {
+        let _ = fs::rename(&backup, &current_exe);
+        let _ = fs::remove_file(&temp_path);
+        return Err(writing(e));
     }
 
-    // Replace the current binary
-    fs::rename(&temp_path, &current_exe).map_err(|e| Error::GraphQL(e.to_string()))?;
+    Ok(())
+}
+
+/// Swap the running binary with its backup. Running it again undoes the
+/// rollback.
+fn rollback() -> Result<()> {
+    let current_exe =
+        env::current_exe().map_err(|e| Error::io("failed to locate the running executable", e))?;
+    let backup = backup_path(&current_exe);
+    if !backup.exists() {
+        return Err(Error::Update(format!(
+            "no previous version to roll back to ({} does not exist)",
+            backup.display()
+        )));
+    }
+    let writing = |e| write_error(&current_exe, e);
 
-    println!("Updated to v{}!", latest_version);
+    let temp_path = current_exe.with_extension("new");
+    fs::rename(&current_exe, &temp_path).map_err(writing)?;
+    if let Err(e) = fs::rename(&backup, &current_exe) {
+        let _ = fs::rename(&temp_path, &current_exe);
+        return Err(writing(e));
+    }
+    fs::rename(&temp_path, &backup).map_err(writing)?;
 
+    println!(
+        "Rolled back from v{} to the previous version",
+        CURRENT_VERSION
+    );
+    println!("Run `linear update --rollback` again to undo");
     Ok(())
 }
+
+fn write_error(exe: &Path, e: std::io::Error) -> Error {
+    let denied = e.kind() == std::io::ErrorKind::PermissionDenied;
+    let error = Error::io(format!("failed to write {}", exe.display()), e);
+    if denied {
+        error.with_hint("re-run with sudo, or reinstall linear somewhere you can write to")
+    } else {
+        error
+    }
+}
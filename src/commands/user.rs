@@ -1,9 +1,10 @@
 use clap::Subcommand;
-use serde::Deserialize;
+use cynic::QueryBuilder;
 
 use crate::client::LinearClient;
 use crate::error::Result;
 use crate::output;
+use crate::queries::user::{User, ViewerQuery};
 
 #[derive(Subcommand)]
 pub enum UserCommands {
@@ -11,34 +12,14 @@ pub enum UserCommands {
     Me,
 }
 
-#[derive(Deserialize)]
-struct ViewerResponse {
-    viewer: Viewer,
+/// Fetch the user the client's credential belongs to.
+pub async fn fetch_viewer(client: &LinearClient) -> Result<User> {
+    let response = client.run(ViewerQuery::build(())).await?;
+    Ok(response.viewer)
 }
 
-#[derive(Deserialize)]
-struct Viewer {
-    id: String,
-    name: String,
-    email: String,
-}
-
-const VIEWER_QUERY: &str = r#"
-    query Viewer {
-        viewer {
-            id
-            name
-            email
-        }
-    }
-"#;
-
 pub async fn handle_me(client: &LinearClient) -> Result<()> {
-    let response: ViewerResponse = client.query(VIEWER_QUERY, serde_json::json!({})).await?;
-    output::print_user(
-        &response.viewer.name,
-        &response.viewer.email,
-        &response.viewer.id,
-    );
+    let viewer = fetch_viewer(client).await?;
+    output::print_user(&viewer);
     Ok(())
 }
@@ -1,9 +1,12 @@
 use clap::{Args, Subcommand};
-use serde::Deserialize;
+use clap_complete::ArgValueCandidates;
 
 use crate::client::LinearClient;
+use crate::complete;
+use crate::config::Profile;
 use crate::error::Result;
 use crate::output;
+use crate::resolve;
 
 #[derive(Subcommand)]
 pub enum WorkflowCommands {
@@ -13,60 +16,20 @@ pub enum WorkflowCommands {
 
 #[derive(Args)]
 pub struct ListWorkflowArgs {
-    /// Team key (required)
-    #[arg(long)]
-    pub team: String,
+    /// Team key [default: the profile's default_team]
+    #[arg(long, add = ArgValueCandidates::new(complete::teams))]
+    pub team: Option<String>,
 }
 
-#[derive(Deserialize)]
-struct TeamResponse {
-    team: TeamWithStates,
-}
-
-#[derive(Deserialize)]
-struct TeamWithStates {
-    states: StatesConnection,
-}
-
-#[derive(Deserialize)]
-struct StatesConnection {
-    nodes: Vec<WorkflowState>,
-}
-
-#[derive(Deserialize, Clone)]
-pub struct WorkflowState {
-    #[allow(dead_code)]
-    pub id: String,
-    pub name: String,
-    #[serde(rename = "type")]
-    pub state_type: String,
-    #[allow(dead_code)]
-    pub color: String,
-    pub position: f64,
-}
-
-const WORKFLOW_STATES_QUERY: &str = r#"
-    query WorkflowStates($teamId: String!) {
-        team(id: $teamId) {
-            states {
-                nodes {
-                    id
-                    name
-                    type
-                    color
-                    position
-                }
-            }
-        }
-    }
-"#;
-
-pub async fn handle_list(client: &LinearClient, args: &ListWorkflowArgs) -> Result<()> {
-    let variables = serde_json::json!({ "teamId": args.team });
-    let response: TeamResponse = client.query(WORKFLOW_STATES_QUERY, variables).await?;
+pub async fn handle_list(
+    client: &LinearClient,
+    args: &ListWorkflowArgs,
+    profile: &Profile,
+) -> Result<()> {
+    let team = resolve::team(client, profile.team(args.team.as_deref())?).await?;
+    let mut states = resolve::states(client, team.id.inner()).await?;
 
     // Sort states by position for display
-    let mut states = response.team.states.nodes;
     states.sort_by(|a, b| {
         a.position
             .partial_cmp(&b.position)
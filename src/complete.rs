@@ -0,0 +1,113 @@
+//! Shell completion.
+//!
+//! `linear completion <shell>` prints a script that asks `linear` itself for
+//! candidates (clap_complete's dynamic completion, activated by the
+//! `COMPLETE` environment variable). Besides subcommands and flags, the
+//! values of `--team`, `--state`, `--project` and issue arguments are
+//! completed from the metadata cache and the `linear sync` mirror of the
+//! active profile. Completion never touches the network, so it only offers
+//! what earlier commands have fetched.
+
+use clap::CommandFactory;
+use clap_complete::CompletionCandidate;
+use serde::Deserialize;
+
+use crate::cache::{Cache, Mode};
+use crate::config::Config;
+use crate::store::Store;
+
+/// Recent issues offered for issue arguments.
+const RECENT_ISSUES: u32 = 50;
+
+/// Answer a completion request from a script printed by `linear
+/// completion`, exiting once done. Does nothing on a normal run.
+pub fn respond() {
+    clap_complete::CompleteEnv::with_factory(crate::Cli::command).complete();
+}
+
+/// The active profile's cache, ignoring `--profile` since completion only
+/// sees the word being completed.
+fn cache() -> Option<Cache> {
+    Cache::new(&profile()?, Mode::Use).ok()
+}
+
+fn profile() -> Option<String> {
+    let selected = std::env::var("LINEAR_PROFILE").ok();
+    Config::load()
+        .ok()
+        .map(|config| config.active_profile_name(selected.as_deref()))
+}
+
+#[derive(Deserialize)]
+struct Named {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct TeamKey {
+    key: String,
+    name: String,
+}
+
+/// Cached team keys, described by the team's name.
+pub fn teams() -> Vec<CompletionCandidate> {
+    let teams: Vec<TeamKey> = cache()
+        .and_then(|cache| cache.peek("teams"))
+        .unwrap_or_default();
+    teams
+        .into_iter()
+        .map(|t| CompletionCandidate::new(t.key).help(Some(t.name.into())))
+        .collect()
+}
+
+/// Cached workflow state names across every team.
+pub fn states() -> Vec<CompletionCandidate> {
+    let Some(cache) = cache() else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = cache
+        .status()
+        .into_iter()
+        .filter(|entry| entry.key.starts_with("states/"))
+        .filter_map(|entry| cache.peek::<Vec<Named>>(&entry.key))
+        .flatten()
+        .map(|state| state.name)
+        .collect();
+    names.sort();
+    names.dedup();
+    names.into_iter().map(CompletionCandidate::new).collect()
+}
+
+/// Cached label names.
+pub fn labels() -> Vec<CompletionCandidate> {
+    named("labels")
+}
+
+/// Cached project names.
+pub fn projects() -> Vec<CompletionCandidate> {
+    named("projects")
+}
+
+fn named(key: &str) -> Vec<CompletionCandidate> {
+    let mut names: Vec<String> = cache()
+        .and_then(|cache| cache.peek::<Vec<Named>>(key))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|n| n.name)
+        .collect();
+    names.sort();
+    names.dedup();
+    names.into_iter().map(CompletionCandidate::new).collect()
+}
+
+/// Identifiers of the most recently updated issues in the `linear sync`
+/// mirror, described by their titles.
+pub fn issues() -> Vec<CompletionCandidate> {
+    profile()
+        .and_then(|profile| Store::open_existing(&profile).ok())
+        .and_then(|store| store.recent_issues(RECENT_ISSUES).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(identifier, title)| CompletionCandidate::new(identifier).help(Some(title.into())))
+        .collect()
+}
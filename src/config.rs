@@ -0,0 +1,317 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::auth;
+use crate::error::{Error, Result};
+use crate::output::Format;
+
+/// Profile used when none is selected and the config names no default.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Contents of `~/.config/linear/config.toml`.
+#[derive(Default, Deserialize, Serialize)]
+pub struct Config {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+#[derive(Default, Clone, Deserialize, Serialize)]
+pub struct Profile {
+    /// The profile's name in the config file
+    #[serde(skip)]
+    pub name: String,
+    /// Personal API key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Shell command whose output is the API key (e.g. a password manager)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_command: Option<String>,
+    /// Team key used when a command's --team is omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_team: Option<String>,
+    /// Output format used when --format is omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<Format>,
+    /// GraphQL endpoint override
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    /// Retries for failed requests when --retries is omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+    /// Request timeout in seconds when --timeout is omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// Whether mutations are retried as well as queries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_mutations: Option<bool>,
+    /// Whether to say when a newer release is out; on unless set to false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_notifier: Option<bool>,
+    /// Default filters for `issue list`
+    #[serde(default, skip_serializing_if = "IssueListDefaults::is_empty")]
+    pub issue_list: IssueListDefaults,
+    /// OAuth application used by `linear auth login --oauth`
+    #[serde(default, skip_serializing_if = "OAuthSettings::is_empty")]
+    pub oauth: OAuthSettings,
+}
+
+#[derive(Default, Clone, Deserialize, Serialize)]
+pub struct IssueListDefaults {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+impl IssueListDefaults {
+    fn is_empty(&self) -> bool {
+        self.team.is_none()
+            && self.state.is_none()
+            && self.assignee.is_none()
+            && self.limit.is_none()
+    }
+}
+
+#[derive(Default, Clone, Deserialize, Serialize)]
+pub struct OAuthSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorize_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_url: Option<String>,
+    /// Comma-separated scopes to request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<String>,
+    /// Loopback port the authorization redirect is received on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_port: Option<u16>,
+}
+
+impl OAuthSettings {
+    fn is_empty(&self) -> bool {
+        self.client_id.is_none()
+            && self.client_secret.is_none()
+            && self.authorize_url.is_none()
+            && self.token_url.is_none()
+            && self.scopes.is_none()
+            && self.redirect_port.is_none()
+    }
+}
+
+/// Keys accepted by `linear config get/set/unset`.
+pub const KEYS: &[&str] = &[
+    "api_key",
+    "api_key_command",
+    "default_team",
+    "format",
+    "api_url",
+    "retries",
+    "timeout",
+    "retry_mutations",
+    "update_notifier",
+    "issue_list.team",
+    "issue_list.state",
+    "issue_list.assignee",
+    "issue_list.limit",
+    "oauth.client_id",
+    "oauth.client_secret",
+    "oauth.authorize_url",
+    "oauth.token_url",
+    "oauth.scopes",
+    "oauth.redirect_port",
+];
+
+/// Keys whose values are masked by `linear config list`.
+pub const SECRET_KEYS: &[&str] = &["api_key", "oauth.client_secret"];
+
+/// Directory holding the config file: `$XDG_CONFIG_HOME/linear`, falling
+/// back to `~/.config/linear` on every platform.
+pub fn dir() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        return Ok(PathBuf::from(dir).join("linear"));
+    }
+    dirs::home_dir()
+        .map(|home| home.join(".config").join("linear"))
+        .ok_or_else(|| Error::Config("could not determine home directory".to_string()))
+}
+
+pub fn path() -> Result<PathBuf> {
+    Ok(dir()?.join("config.toml"))
+}
+
+impl Config {
+    /// Load the config file, or an empty config if it doesn't exist yet.
+    pub fn load() -> Result<Self> {
+        let path = path()?;
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(Error::io(format!("failed to read {}", path.display()), e)),
+        };
+        toml::from_str(&contents)
+            .map_err(|e| Error::Config(format!("invalid {}: {}", path.display(), e)))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = path()?;
+        let contents = toml::to_string_pretty(self)
+            .map_err(|e| Error::Config(format!("failed to serialize config: {}", e)))?;
+        // Profiles can hold `api_key` and OAuth client secrets, so the file
+        // gets the same 0600/0700 treatment as the credentials store.
+        if let Some(parent) = path.parent() {
+            auth::create_private_dir(parent)?;
+        }
+        auth::write_private(&path, contents.as_bytes())
+            .map_err(|e| Error::io(format!("failed to write {}", path.display()), e))
+    }
+
+    /// Name of the active profile: `--profile`/`LINEAR_PROFILE`, then the
+    /// configured default, then `default`.
+    pub fn active_profile_name(&self, selected: Option<&str>) -> String {
+        selected
+            .map(str::to_string)
+            .or_else(|| self.default_profile.clone())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+    }
+
+    /// Look up a profile by name. The implicit `default` profile always
+    /// exists, even when the config file doesn't define it.
+    pub fn profile(&self, name: &str) -> Result<Profile> {
+        let mut profile = match self.profiles.get(name) {
+            Some(profile) => profile.clone(),
+            None if name == DEFAULT_PROFILE => Profile::default(),
+            None => {
+                return Err(Error::Config(format!(
+                    "profile '{}' not found in {}",
+                    name,
+                    path()?.display()
+                )))
+            }
+        };
+        profile.name = name.to_string();
+        Ok(profile)
+    }
+}
+
+impl Profile {
+    /// The team to use when a command's --team flag was omitted.
+    pub fn team<'a>(&'a self, flag: Option<&'a str>) -> Result<&'a str> {
+        flag.or(self.default_team.as_deref()).ok_or_else(|| {
+            Error::InvalidInput("no team given; pass --team or set default_team".to_string())
+        })
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        let value = match key {
+            "api_key" => self.api_key.clone(),
+            "api_key_command" => self.api_key_command.clone(),
+            "default_team" => self.default_team.clone(),
+            "format" => self.format.map(format_name),
+            "api_url" => self.api_url.clone(),
+            "retries" => self.retries.map(|r| r.to_string()),
+            "timeout" => self.timeout.map(|t| t.to_string()),
+            "retry_mutations" => self.retry_mutations.map(|r| r.to_string()),
+            "update_notifier" => self.update_notifier.map(|u| u.to_string()),
+            "issue_list.team" => self.issue_list.team.clone(),
+            "issue_list.state" => self.issue_list.state.clone(),
+            "issue_list.assignee" => self.issue_list.assignee.clone(),
+            "issue_list.limit" => self.issue_list.limit.map(|l| l.to_string()),
+            "oauth.client_id" => self.oauth.client_id.clone(),
+            "oauth.client_secret" => self.oauth.client_secret.clone(),
+            "oauth.authorize_url" => self.oauth.authorize_url.clone(),
+            "oauth.token_url" => self.oauth.token_url.clone(),
+            "oauth.scopes" => self.oauth.scopes.clone(),
+            "oauth.redirect_port" => self.oauth.redirect_port.map(|p| p.to_string()),
+            _ => return Err(unknown_key(key)),
+        };
+        Ok(value)
+    }
+
+    /// Set `key` to `value`, or clear it when `value` is `None`.
+    pub fn set(&mut self, key: &str, value: Option<String>) -> Result<()> {
+        match key {
+            "api_key" => self.api_key = value,
+            "api_key_command" => self.api_key_command = value,
+            "default_team" => self.default_team = value,
+            "format" => self.format = value.as_deref().map(parse_format).transpose()?,
+            "api_url" => self.api_url = value,
+            "retries" => self.retries = parse_number(key, value)?,
+            "timeout" => self.timeout = parse_number(key, value)?,
+            "retry_mutations" => self.retry_mutations = parse_bool(key, value)?,
+            "update_notifier" => self.update_notifier = parse_bool(key, value)?,
+            "issue_list.team" => self.issue_list.team = value,
+            "issue_list.state" => self.issue_list.state = value,
+            "issue_list.assignee" => self.issue_list.assignee = value,
+            "issue_list.limit" => self.issue_list.limit = parse_number(key, value)?,
+            "oauth.client_id" => self.oauth.client_id = value,
+            "oauth.client_secret" => self.oauth.client_secret = value,
+            "oauth.authorize_url" => self.oauth.authorize_url = value,
+            "oauth.token_url" => self.oauth.token_url = value,
+            "oauth.scopes" => self.oauth.scopes = value,
+            "oauth.redirect_port" => self.oauth.redirect_port = parse_number(key, value)?,
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+}
+
+fn format_name(format: Format) -> String {
+    use clap::ValueEnum;
+    format
+        .to_possible_value()
+        .expect("formats have names")
+        .get_name()
+        .to_string()
+}
+
+fn parse_format(value: &str) -> Result<Format> {
+    use clap::ValueEnum;
+    Format::from_str(value, true).map_err(|_| {
+        Error::InvalidInput(format!(
+            "unknown format '{}' (expected table, json, ndjson, yaml, csv or tsv)",
+            value
+        ))
+    })
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: Option<String>) -> Result<Option<T>> {
+    value
+        .map(|v| {
+            v.parse()
+                .map_err(|_| Error::InvalidInput(format!("{} must be a number, got '{}'", key, v)))
+        })
+        .transpose()
+}
+
+fn parse_bool(key: &str, value: Option<String>) -> Result<Option<bool>> {
+    value
+        .map(|v| match v.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "1" => Ok(true),
+            "false" | "no" | "0" => Ok(false),
+            _ => Err(Error::InvalidInput(format!(
+                "{} must be true or false, got '{}'",
+                key, v
+            ))),
+        })
+        .transpose()
+}
+
+fn unknown_key(key: &str) -> Error {
+    Error::InvalidInput(format!(
+        "unknown config key '{}' (expected one of: {})",
+        key,
+        KEYS.join(", ")
+    ))
+}
@@ -2,33 +2,127 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum Error {
-    #[error("LINEAR_API_KEY environment variable not set")]
+    #[error("Not logged in: run `linear auth login` or set LINEAR_API_KEY")]
     MissingApiKey,
 
     #[error("Authentication failed: invalid API key")]
     Unauthorized,
 
+    #[error("OAuth error: {0}")]
+    OAuth(String),
+
     #[error("Not found: {0}")]
-    #[allow(dead_code)] // Reserved for future use
     NotFound(String),
 
+    #[error("Ambiguous {0}")]
+    Ambiguous(String),
+
     #[error("Rate limited, retry after {0} seconds")]
     RateLimited(u64),
 
+    #[error("Config error: {0}")]
+    Config(String),
+
     #[error("GraphQL error: {0}")]
     GraphQL(String),
 
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    /// A command-line argument or value that can't be used as given
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    #[error("{context}: {source}")]
+    Io {
+        context: String,
+        source: std::io::Error,
+    },
+
+    #[error("Update failed: {0}")]
+    Update(String),
+
     #[error("Network error: {0}")]
     Network(#[from] reqwest::Error),
+
+    #[error("Local store error: {0}")]
+    Store(#[from] rusqlite::Error),
+
+    /// Another error together with a suggestion for fixing it, which
+    /// `output::print_error` shows on its own line.
+    #[error("{error}")]
+    Hinted { error: Box<Error>, hint: String },
 }
 
 impl Error {
+    /// An I/O failure, described by what was being attempted, e.g.
+    /// "failed to write /usr/local/bin/linear".
+    pub fn io(context: impl Into<String>, source: std::io::Error) -> Self {
+        Error::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    /// Attach a suggestion for fixing this error, unless it already has one.
+    pub fn with_hint(self, hint: impl Into<String>) -> Self {
+        match self {
+            Error::Hinted { .. } => self,
+            error => Error::Hinted {
+                error: Box::new(error),
+                hint: hint.into(),
+            },
+        }
+    }
+
+    /// Whether this is a [`Error::NotFound`], hinted or not.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Error::Hinted { error, .. } => error.is_not_found(),
+            Error::NotFound(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn hint(&self) -> Option<&str> {
+        match self {
+            Error::Hinted { hint, .. } => Some(hint),
+            _ => None,
+        }
+    }
+
     pub fn exit_code(&self) -> i32 {
         match self {
-            Error::MissingApiKey | Error::Unauthorized => 2,
+            Error::Hinted { error, .. } => error.exit_code(),
+            Error::MissingApiKey | Error::Unauthorized | Error::OAuth(_) => 2,
             Error::NotFound(_) => 3,
             Error::RateLimited(_) => 4,
-            _ => 1,
+            Error::InvalidInput(_) | Error::Validation(_) | Error::Ambiguous(_) => 5,
+            Error::Config(_) => 6,
+            Error::Io { .. } => 7,
+            Error::Update(_) => 8,
+            Error::GraphQL(_) | Error::Network(_) | Error::Store(_) => 1,
+        }
+    }
+
+    /// A stable name for the kind of error, for machine-readable output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Hinted { error, .. } => error.kind(),
+            Error::MissingApiKey => "missing_api_key",
+            Error::Unauthorized => "unauthorized",
+            Error::OAuth(_) => "oauth",
+            Error::NotFound(_) => "not_found",
+            Error::Ambiguous(_) => "ambiguous",
+            Error::RateLimited(_) => "rate_limited",
+            Error::Config(_) => "config",
+            Error::GraphQL(_) => "graphql",
+            Error::Validation(_) => "validation",
+            Error::InvalidInput(_) => "invalid_input",
+            Error::Io { .. } => "io",
+            Error::Update(_) => "update",
+            Error::Network(_) => "network",
+            Error::Store(_) => "store",
         }
     }
 }
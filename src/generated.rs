@@ -1,2 +1,22 @@
+//! Types generated from `schema.graphql`. Query fragments in `queries` are
+//! checked against this schema at compile time.
+
 #[cynic::schema("linear")]
-mod schema {}
+pub mod schema {}
+
+/// Read date scalars as the ISO 8601 strings Linear sends. Only the output
+/// side of `impl_scalar!` is needed (and possible, as `String` is already a
+/// variable type).
+macro_rules! string_scalar {
+    ($scalar:ident) => {
+        impl cynic::schema::IsScalar<schema::$scalar> for String {
+            type SchemaType = schema::$scalar;
+        }
+
+        impl cynic::coercions::CoercesTo<schema::$scalar> for String {}
+    };
+}
+
+string_scalar!(DateTime);
+string_scalar!(DateTimeOrDuration);
+string_scalar!(TimelessDate);
@@ -0,0 +1,226 @@
+//! Long text for issue descriptions, comments and documents, read from a
+//! file or stdin, or written in the user's editor.
+//!
+//! The editor opens on a temporary Markdown file. Drafts with fields (an
+//! issue's title, team and labels, say) put them in a front matter block of
+//! `key: value` lines between `---` lines, above the body. Saving the file
+//! unchanged, or leaving it empty, aborts the command.
+
+use std::fs;
+use std::io::{IsTerminal, Read};
+use std::path::Path;
+use std::process::Command;
+
+use crate::error::{Error, Result};
+
+/// Read a file named on the command line, with `-` meaning stdin.
+pub fn read_file(path: &str) -> Result<String> {
+    let mut contents = String::new();
+    let read = if path == "-" {
+        std::io::stdin().read_to_string(&mut contents).map(|_| ())
+    } else {
+        fs::read_to_string(path).map(|c| contents = c)
+    };
+    read.map_err(|e| Error::io(format!("failed to read {}", path), e))?;
+    Ok(contents)
+}
+
+/// Text given inline or with a `--*-file` flag, whichever was used.
+pub fn text(inline: Option<&str>, file: Option<&str>) -> Result<Option<String>> {
+    match (inline, file) {
+        (Some(text), _) => Ok(Some(text.to_string())),
+        (None, Some(path)) => read_file(path).map(Some),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Whether the user can be asked for what is missing, in the editor or
+/// with a prompt: stdin is a terminal to read from, and stdout and stderr
+/// are terminals for the editor and prompts to draw on.
+pub fn interactive() -> bool {
+    std::io::stdin().is_terminal()
+        && std::io::stdout().is_terminal()
+        && std::io::stderr().is_terminal()
+}
+
+/// Whether to open the editor: when asked to with `-e`, or when the text
+/// is missing and there is a terminal to write it in.
+pub fn wants_editor(asked: bool, missing: bool) -> bool {
+    asked || (missing && interactive())
+}
+
+/// The error for a required flag that was omitted where nothing can ask
+/// for it.
+pub fn missing(flag: &str) -> Error {
+    Error::InvalidInput(format!("{} is required", flag)).with_hint(format!(
+        "pass {}, or run in a terminal to be asked for it",
+        flag
+    ))
+}
+
+/// Text to edit: front matter fields, then a Markdown body.
+pub struct Draft {
+    pub fields: Vec<(&'static str, String)>,
+    pub body: String,
+}
+
+impl Draft {
+    pub fn new(body: Option<&str>) -> Self {
+        Self {
+            fields: Vec::new(),
+            body: body.unwrap_or_default().to_string(),
+        }
+    }
+
+    pub fn field(mut self, key: &'static str, value: Option<&str>) -> Self {
+        self.fields
+            .push((key, value.unwrap_or_default().to_string()));
+        self
+    }
+
+    /// A field's value, or `None` when it was left empty.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| value.as_str())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// The body, or `None` when it was left empty.
+    pub fn body(&self) -> Option<&str> {
+        Some(self.body.as_str()).filter(|body| !body.is_empty())
+    }
+
+    fn render(&self) -> String {
+        if self.fields.is_empty() {
+            return self.body.clone();
+        }
+        let mut text = String::from("---\n");
+        for (key, value) in &self.fields {
+            text.push_str(&format!("{}: {}\n", key, value));
+        }
+        text.push_str("---\n\n");
+        text.push_str(&self.body);
+        text
+    }
+
+    /// Read back an edited draft, keeping the fields this draft has.
+    fn parse(&self, text: &str) -> Result<Self> {
+        let mut fields: Vec<(&'static str, String)> = self
+            .fields
+            .iter()
+            .map(|(k, _)| (*k, String::new()))
+            .collect();
+        let mut body = text;
+
+        if !fields.is_empty() {
+            let rest = text.strip_prefix("---\n").ok_or_else(|| {
+                Error::InvalidInput("the front matter is missing its opening `---`".to_string())
+            })?;
+            let (header, rest) = rest
+                .split_once("\n---")
+                .or_else(|| rest.strip_prefix("---").map(|rest| ("", rest)))
+                .ok_or_else(|| {
+                    Error::InvalidInput("the front matter is missing its closing `---`".to_string())
+                })?;
+            for line in header.lines().filter(|l| !l.trim().is_empty()) {
+                let (key, value) = line.split_once(':').ok_or_else(|| {
+                    Error::InvalidInput(format!(
+                        "expected `key: value` in the front matter, got '{}'",
+                        line
+                    ))
+                })?;
+                let field = fields
+                    .iter_mut()
+                    .find(|(k, _)| *k == key.trim())
+                    .ok_or_else(|| {
+                        Error::InvalidInput(format!(
+                            "unknown field '{}' in the front matter",
+                            key.trim()
+                        ))
+                    })?;
+                field.1 = value.trim().to_string();
+            }
+            body = rest.split_once('\n').map_or("", |(_, body)| body);
+        }
+
+        Ok(Self {
+            fields,
+            body: body.trim().to_string(),
+        })
+    }
+}
+
+/// Open `$VISUAL` or `$EDITOR` on the draft and return what was saved.
+/// `name` becomes part of the temporary file's name, which some editors
+/// show.
+pub fn edit(name: &str, draft: &Draft) -> Result<Draft> {
+    use std::io::Write;
+
+    let initial = draft.render();
+    // Created exclusively and readable only by the user; removed on drop.
+    let mut file = tempfile::Builder::new()
+        .prefix(&format!("linear-{}-", name))
+        .suffix(".md")
+        .tempfile()
+        .map_err(|e| Error::io("failed to create a temporary file", e))?;
+    file.write_all(initial.as_bytes())
+        .and_then(|_| file.flush())
+        .map_err(|e| Error::io(format!("failed to write {}", file.path().display()), e))?;
+    // Close our handle so the editor is free to replace the file
+    let path = file.into_temp_path();
+
+    open(&path)?;
+    let edited = fs::read_to_string(&path)
+        .map_err(|e| Error::io(format!("failed to read {}", path.display()), e))?;
+
+    if edited == initial {
+        return Err(Error::InvalidInput(
+            "aborted, the file was saved unchanged".to_string(),
+        ));
+    }
+    let edited = draft.parse(&edited)?;
+    if edited.body.is_empty() && edited.fields.iter().all(|(_, value)| value.is_empty()) {
+        return Err(Error::InvalidInput(
+            "aborted, the file was saved empty".to_string(),
+        ));
+    }
+    Ok(edited)
+}
+
+fn editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string())
+}
+
+/// Run the editor on `path` and wait for it to exit. The editor setting
+/// goes through the shell, so it may carry arguments (e.g. `code --wait`).
+fn open(path: &Path) -> Result<()> {
+    let editor = editor();
+    #[cfg(unix)]
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$@\"", editor))
+        .arg(&editor)
+        .arg(path)
+        .status();
+    #[cfg(not(unix))]
+    let status = Command::new("cmd")
+        .arg("/C")
+        .arg(format!("{} \"{}\"", editor, path.display()))
+        .status();
+
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(Error::InvalidInput(format!(
+            "editor `{}` exited with {}",
+            editor, status
+        ))
+        .with_hint("set $VISUAL or $EDITOR to the editor to use")),
+        Err(e) => Err(Error::io(format!("failed to start editor `{}`", editor), e)),
+    }
+}
@@ -1,8 +1,23 @@
+mod auth;
+mod cache;
 mod client;
 mod commands;
+mod complete;
+mod config;
 mod error;
 mod generated;
+mod input;
+mod notifier;
+mod oauth;
 mod output;
+mod prompt;
+mod queries;
+mod resolve;
+mod store;
+mod time;
+mod trace;
+mod transport;
+mod validate;
 
 use clap::Parser;
 use commands::Commands;
@@ -15,32 +30,225 @@ struct Cli {
     #[arg(long, global = true)]
     no_color: bool,
 
+    /// Output format [default: table, or the profile's format]
+    #[arg(long, global = true, value_enum)]
+    format: Option<output::Format>,
+
+    /// Shorthand for --format json
+    #[arg(long, global = true, conflicts_with = "format")]
+    json: bool,
+
+    /// Config profile to use
+    #[arg(long, global = true, env = "LINEAR_PROFILE")]
+    profile: Option<String>,
+
+    /// Times to retry rate-limited or failed requests [default: 3]
+    #[arg(long, global = true)]
+    retries: Option<u32>,
+
+    /// Request timeout in seconds [default: 30]
+    #[arg(long, global = true)]
+    timeout: Option<u64>,
+
+    /// Retry mutations as well as queries (a retried mutation may be applied
+    /// twice)
+    #[arg(long, global = true)]
+    retry_mutations: bool,
+
+    /// Bypass the local metadata cache entirely
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// Refetch cached metadata (teams, states, users, labels, projects)
+    /// instead of using the cache
+    #[arg(long, global = true, conflicts_with = "no_cache")]
+    refresh: bool,
+
+    /// Answer read commands from the local copy made by `linear sync`
+    /// instead of the API
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Save every API request and response to FILE (a cassette), with
+    /// credentials and personal fields redacted, e.g. to share a bug report
+    #[arg(long, global = true, value_name = "FILE")]
+    record: Option<std::path::PathBuf>,
+
+    /// Answer API requests from a cassette saved with --record instead of
+    /// the network
+    #[arg(long, global = true, value_name = "FILE", env = "LINEAR_REPLAY")]
+    replay: Option<std::path::PathBuf>,
+
+    /// Also redact this field when recording (repeatable)
+    #[arg(long, global = true, value_name = "FIELD", requires = "record")]
+    redact: Vec<String>,
+
+    /// Log each request's operation, status, latency and size, and any
+    /// retries, to stderr
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    /// Like --verbose, and also log variables and rate-limit headers
+    #[arg(
+        long,
+        global = true,
+        env = "LINEAR_DEBUG",
+        value_parser = clap::builder::BoolishValueParser::new()
+    )]
+    debug: bool,
+
+    /// Log format for --verbose and --debug
+    #[arg(long, global = true, value_enum, default_value_t)]
+    debug_format: trace::Format,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[tokio::main]
 async fn main() {
+    complete::respond();
     let cli = Cli::parse();
 
     // Handle NO_COLOR env var and --no-color flag
-    if cli.no_color || std::env::var("NO_COLOR").is_ok() {
+    let color = !cli.no_color && std::env::var("NO_COLOR").is_err();
+    if !color {
         owo_colors::set_override(false);
     }
 
-    if let Err(e) = run(cli).await {
-        output::print_error(&e);
-        std::process::exit(e.exit_code());
+    let level = if cli.debug {
+        tracing::level_filters::LevelFilter::DEBUG
+    } else if cli.verbose {
+        tracing::level_filters::LevelFilter::INFO
+    } else {
+        tracing::level_filters::LevelFilter::OFF
+    };
+    trace::init(level, cli.debug_format, color);
+
+    // An explicit format also applies to errors raised before the config is
+    // loaded; otherwise the profile's format is set once it is
+    if cli.json {
+        output::set_format(output::Format::Json);
+    } else if let Some(format) = cli.format {
+        output::set_format(format);
+    }
+
+    transport::configure(transport::Options {
+        replay: cli.replay.clone(),
+        record: cli.record.clone(),
+        redact: cli.redact.clone(),
+    });
+
+    let notifier = match cli.command {
+        Commands::Update(_) | Commands::Completion(_) | Commands::Docs(_) => None,
+        _ => notifier::start(cli.profile.as_deref(), cli.offline),
+    };
+
+    let result = match &cli.command {
+        // Update doesn't need a Linear API key, and `--check` answers with
+        // its exit code
+        Commands::Update(args) => commands::update::handle_update(args)
+            .await
+            .map(commands::update::Outcome::exit_code),
+        _ => run(cli).await.map(|()| 0),
+    };
+    match result {
+        Ok(code) => {
+            if let Some(notifier) = notifier {
+                notifier.finish().await;
+            }
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
+        Err(e) => {
+            output::print_error(&e);
+            std::process::exit(e.exit_code());
+        }
     }
 }
 
 async fn run(cli: Cli) -> error::Result<()> {
-    // Handle update command separately (doesn't need Linear API key)
-    if let Commands::Update = cli.command {
-        return commands::update::handle_update().await;
+    if let Commands::Completion(args) = &cli.command {
+        return commands::completion::handle_completion(args);
+    }
+    if let Commands::Docs(args) = &cli.command {
+        return commands::docs::handle_docs(args);
+    }
+
+    let config = config::Config::load()?;
+    let profile_name = config.active_profile_name(cli.profile.as_deref());
+
+    let profile_format = config.profiles.get(&profile_name).and_then(|p| p.format);
+    output::set_format(profile_format.unwrap_or_default());
+
+    // Config commands manage the profiles themselves, so they don't need one
+    // to be valid
+    if let Commands::Config { command } = &cli.command {
+        let selected = cli.profile.as_deref();
+        return match command {
+            commands::config::ConfigCommands::Get(args) => {
+                commands::config::handle_get(selected, args)
+            }
+            commands::config::ConfigCommands::Set(args) => {
+                commands::config::handle_set(selected, args)
+            }
+            commands::config::ConfigCommands::Unset(args) => {
+                commands::config::handle_unset(selected, args)
+            }
+            commands::config::ConfigCommands::List => commands::config::handle_list(selected),
+            commands::config::ConfigCommands::Profiles => {
+                commands::config::handle_profiles(selected)
+            }
+        };
+    }
+
+    let profile = config.profile(&profile_name)?;
+
+    // Auth commands work with credentials directly rather than through the
+    // profile's client
+    if let Commands::Auth { command } = &cli.command {
+        return match command {
+            commands::auth::AuthCommands::Login(args) => {
+                commands::auth::handle_login(&profile, args).await
+            }
+            commands::auth::AuthCommands::Logout => commands::auth::handle_logout(&profile),
+            commands::auth::AuthCommands::Status => commands::auth::handle_status(&profile).await,
+        };
     }
 
-    let client = client::LinearClient::from_env()?;
+    if let Commands::Cache { command } = &cli.command {
+        return match command {
+            commands::cache::CacheCommands::Status => commands::cache::handle_status(&profile.name),
+            commands::cache::CacheCommands::Clear(args) => {
+                commands::cache::handle_clear(&profile.name, args)
+            }
+        };
+    }
+
+    if cli.offline {
+        return run_offline(&cli.command, &profile);
+    }
+
+    let defaults = client::RetryPolicy::default();
+    let retry = client::RetryPolicy {
+        retries: cli.retries.or(profile.retries).unwrap_or(defaults.retries),
+        timeout: cli
+            .timeout
+            .or(profile.timeout)
+            .map_or(defaults.timeout, std::time::Duration::from_secs),
+        retry_mutations: cli.retry_mutations || profile.retry_mutations.unwrap_or_default(),
+    };
+    let cache_mode = if cli.no_cache {
+        cache::Mode::Off
+    } else if cli.refresh {
+        cache::Mode::Refresh
+    } else {
+        cache::Mode::Use
+    };
+    let client = client::LinearClient::from_profile(&profile)?
+        .with_retry_policy(retry)
+        .with_cache(cache::Cache::new(&profile.name, cache_mode)?);
 
     match cli.command {
         Commands::User { command } => match command {
@@ -50,13 +258,13 @@ async fn run(cli: Cli) -> error::Result<()> {
         },
         Commands::Issue { command } => match command {
             commands::issue::IssueCommands::List(args) => {
-                commands::issue::handle_list(&client, &args).await?;
+                commands::issue::handle_list(&client, &args, &profile).await?;
             }
             commands::issue::IssueCommands::Get(args) => {
                 commands::issue::handle_get(&client, &args).await?;
             }
             commands::issue::IssueCommands::Create(args) => {
-                commands::issue::handle_create(&client, &args).await?;
+                commands::issue::handle_create(&client, &args, &profile).await?;
             }
             commands::issue::IssueCommands::Update(args) => {
                 commands::issue::handle_update(&client, &args).await?;
@@ -78,7 +286,7 @@ async fn run(cli: Cli) -> error::Result<()> {
                 commands::project::handle_get(&client, &args).await?;
             }
             commands::project::ProjectCommands::Create(args) => {
-                commands::project::handle_create(&client, &args).await?;
+                commands::project::handle_create(&client, &args, &profile).await?;
             }
         },
         Commands::Cycle { command } => match command {
@@ -96,7 +304,7 @@ async fn run(cli: Cli) -> error::Result<()> {
         },
         Commands::Workflow { command } => match command {
             commands::workflow::WorkflowCommands::List(args) => {
-                commands::workflow::handle_list(&client, &args).await?;
+                commands::workflow::handle_list(&client, &args, &profile).await?;
             }
         },
         Commands::Comment { command } => match command {
@@ -121,8 +329,62 @@ async fn run(cli: Cli) -> error::Result<()> {
         Commands::Search(args) => {
             commands::search::handle_search(&client, &args).await?;
         }
-        Commands::Update => unreachable!(), // Handled above
+        Commands::Sync(args) => {
+            commands::sync::handle_sync(&client, &args, &profile).await?;
+        }
+        Commands::Api(args) => match &args.command {
+            Some(commands::api::ApiCommands::Limits) => {
+                commands::api::handle_limits(&client).await?;
+            }
+            None => {
+                commands::api::handle_query(&client, &args).await?;
+            }
+        },
+        Commands::Update(_)
+        | Commands::Completion(_)
+        | Commands::Docs(_)
+        | Commands::Config { .. }
+        | Commands::Auth { .. }
+        | Commands::Cache { .. } => {
+            unreachable!() // Handled above
+        }
     }
 
     Ok(())
 }
+
+/// Run a read command against the local store.
+fn run_offline(command: &Commands, profile: &config::Profile) -> error::Result<()> {
+    use commands::{comment, cycle, issue, project};
+
+    let store = store::Store::open_existing(&profile.name)?;
+    match command {
+        Commands::Issue {
+            command: issue::IssueCommands::List(args),
+        } => issue::handle_list_offline(&store, args, profile),
+        Commands::Issue {
+            command: issue::IssueCommands::Get(args),
+        } => issue::handle_get_offline(&store, args),
+        Commands::Search(args) => commands::search::handle_search_offline(&store, args),
+        Commands::Comment {
+            command: comment::CommentCommands::List(args),
+        } => comment::handle_list_offline(&store, args),
+        Commands::Project {
+            command: project::ProjectCommands::List(args),
+        } => project::handle_list_offline(&store, args),
+        Commands::Project {
+            command: project::ProjectCommands::Get(args),
+        } => project::handle_get_offline(&store, args),
+        Commands::Cycle {
+            command: cycle::CycleCommands::List(args),
+        } => cycle::handle_list_offline(&store, args),
+        Commands::Cycle {
+            command: cycle::CycleCommands::Get(args),
+        } => cycle::handle_get_offline(&store, args),
+        _ => Err(error::Error::InvalidInput(
+            "--offline only works with issue list/get, search, comment list, project list/get \
+             and cycle list/get"
+                .to_string(),
+        )),
+    }
+}
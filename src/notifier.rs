@@ -0,0 +1,126 @@
+//! The "new version available" notice.
+//!
+//! At most once a day a command looks up the latest release in the
+//! background while it runs, and once it completes, says so on stderr if the
+//! release is newer than the running binary. The last check is remembered in
+//! `update-check.json` in the cache directory. Nothing is checked or printed
+//! unless both stdout and stderr are terminals, with a structured
+//! `--format`, with `--offline` or a replayed cassette, when
+//! `LINEAR_NO_UPDATE_NOTIFIER` is set, or with `update_notifier = false`.
+
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+use crate::cache;
+use crate::commands::update;
+use crate::config::Config;
+use crate::output::{self, Format};
+use crate::time::now;
+use crate::transport;
+
+const STATE_FILE: &str = "update-check.json";
+
+/// Time between checks, and between notices about the same release.
+const INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Longest the release lookup may take before it is given up.
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// Longest a completed command waits for a lookup still in flight.
+const GRACE: Duration = Duration::from_millis(500);
+
+#[derive(Default, Deserialize, Serialize)]
+struct State {
+    /// Unix time of the last lookup, successful or not
+    checked_at: u64,
+    /// Tag of the latest release the last successful lookup found
+    latest: Option<String>,
+    /// Unix time the last notice was printed
+    notified_at: u64,
+}
+
+pub struct Notifier {
+    path: PathBuf,
+    check: Option<JoinHandle<()>>,
+}
+
+/// Start a background lookup if the last one is over a day old. Returns
+/// `None` when notices are disabled for this run, including when it
+/// shouldn't touch the network (`offline`, or a replayed cassette).
+pub fn start(profile: Option<&str>, offline: bool) -> Option<Notifier> {
+    if std::env::var_os("LINEAR_NO_UPDATE_NOTIFIER").is_some_and(|v| !v.is_empty())
+        || !std::io::stdout().is_terminal()
+        || !std::io::stderr().is_terminal()
+        || output::format() != Format::Table
+        || offline
+        || transport::replaying()
+    {
+        return None;
+    }
+    let config = Config::load().ok()?;
+    let profile = config.profile(&config.active_profile_name(profile)).ok()?;
+    if profile.update_notifier == Some(false) {
+        return None;
+    }
+
+    let path = cache::root().ok()?.join(STATE_FILE);
+    let state = read(&path);
+    let check = (now().saturating_sub(state.checked_at) >= INTERVAL.as_secs())
+        .then(|| tokio::spawn(refresh(path.clone())));
+    Some(Notifier { path, check })
+}
+
+impl Notifier {
+    /// Print the notice if a newer release is known and hasn't been
+    /// mentioned today.
+    pub async fn finish(self) {
+        if let Some(check) = self.check {
+            let _ = tokio::time::timeout(GRACE, check).await;
+        }
+        // The profile's format is only known once the command has run
+        if output::format() != Format::Table {
+            return;
+        }
+
+        let mut state = read(&self.path);
+        let Some(latest) = state.latest.as_deref().filter(|tag| update::is_newer(tag)) else {
+            return;
+        };
+        if now().saturating_sub(state.notified_at) < INTERVAL.as_secs() {
+            return;
+        }
+        output::print_update_notice(update::CURRENT_VERSION, latest);
+        state.notified_at = now();
+        write(&self.path, &state);
+    }
+}
+
+async fn refresh(path: PathBuf) {
+    let latest = tokio::time::timeout(TIMEOUT, update::latest_release(false)).await;
+    let mut state = read(&path);
+    state.checked_at = now();
+    if let Ok(Ok(release)) = latest {
+        state.latest = Some(release.tag_name);
+    }
+    write(&path, &state);
+}
+
+fn read(path: &Path) -> State {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Save the state, ignoring failures: a missed notice is harmless.
+fn write(path: &Path, state: &State) {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(contents) = serde_json::to_string(state) {
+        let _ = fs::write(path, contents);
+    }
+}
@@ -0,0 +1,254 @@
+//! OAuth2 authorization code flow with PKCE, receiving the redirect on a
+//! local loopback listener.
+
+use std::time::Duration;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::config::OAuthSettings;
+use crate::error::{Error, Result};
+use crate::time::now;
+
+const DEFAULT_AUTHORIZE_URL: &str = "https://linear.app/oauth/authorize";
+const DEFAULT_TOKEN_URL: &str = "https://api.linear.app/oauth/token";
+const DEFAULT_SCOPES: &str = "read,write";
+const DEFAULT_REDIRECT_PORT: u16 = 8484;
+
+/// How long to wait for the browser to come back with an authorization code.
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Tokens issued by the authorization server.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct OAuthToken {
+    pub access_token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// Expiry as seconds since the Unix epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct TokenError {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// Run the full browser flow and exchange the resulting code for tokens.
+pub async fn authorize(settings: &OAuthSettings) -> Result<OAuthToken> {
+    let client_id = client_id(settings)?;
+    let port = settings.redirect_port.unwrap_or(DEFAULT_REDIRECT_PORT);
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| Error::OAuth(format!("failed to listen on port {}: {}", port, e)))?;
+    let port = listener.local_addr().map(|a| a.port()).unwrap_or(port);
+    let redirect_uri = format!("http://localhost:{}/callback", port);
+
+    let verifier = random_token(32)?;
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    let state = random_token(16)?;
+
+    let authorize_url = url::Url::parse_with_params(
+        settings
+            .authorize_url
+            .as_deref()
+            .unwrap_or(DEFAULT_AUTHORIZE_URL),
+        &[
+            ("client_id", client_id),
+            ("redirect_uri", &redirect_uri),
+            ("response_type", "code"),
+            (
+                "scope",
+                settings.scopes.as_deref().unwrap_or(DEFAULT_SCOPES),
+            ),
+            ("state", &state),
+            ("code_challenge", &challenge),
+            ("code_challenge_method", "S256"),
+        ],
+    )
+    .map_err(|e| Error::Config(format!("invalid oauth.authorize_url: {}", e)))?;
+
+    eprintln!("Open this URL in your browser to authorize linear-cli:");
+    eprintln!("{}", authorize_url);
+    open_browser(authorize_url.as_str());
+
+    let code = tokio::time::timeout(CALLBACK_TIMEOUT, receive_code(&listener, &state))
+        .await
+        .map_err(|_| Error::OAuth("timed out waiting for authorization".to_string()))??;
+
+    request_token(
+        settings,
+        &[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("redirect_uri", &redirect_uri),
+            ("client_id", client_id),
+            ("code_verifier", &verifier),
+        ],
+    )
+    .await
+}
+
+/// Exchange a refresh token for a new access token. The old refresh token is
+/// kept if the server doesn't rotate it.
+pub async fn refresh(settings: &OAuthSettings, refresh_token: &str) -> Result<OAuthToken> {
+    let client_id = client_id(settings)?;
+    let mut token = request_token(
+        settings,
+        &[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", client_id),
+        ],
+    )
+    .await?;
+    token
+        .refresh_token
+        .get_or_insert_with(|| refresh_token.to_string());
+    Ok(token)
+}
+
+fn client_id(settings: &OAuthSettings) -> Result<&str> {
+    settings.client_id.as_deref().ok_or_else(|| {
+        Error::Config(
+            "set oauth.client_id to the client ID of a Linear OAuth application".to_string(),
+        )
+    })
+}
+
+async fn request_token(settings: &OAuthSettings, params: &[(&str, &str)]) -> Result<OAuthToken> {
+    let mut form: Vec<(&str, &str)> = params.to_vec();
+    if let Some(secret) = &settings.client_secret {
+        form.push(("client_secret", secret));
+    }
+
+    let response = reqwest::Client::new()
+        .post(settings.token_url.as_deref().unwrap_or(DEFAULT_TOKEN_URL))
+        .form(&form)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let message = match response.json::<TokenError>().await {
+            Ok(e) => e.error_description.unwrap_or(e.error),
+            Err(_) => status.to_string(),
+        };
+        return Err(Error::OAuth(format!("token request failed: {}", message)));
+    }
+
+    let token: TokenResponse = response.json().await?;
+    Ok(OAuthToken {
+        access_token: token.access_token,
+        refresh_token: token.refresh_token,
+        expires_at: token.expires_in.map(|secs| now() + secs),
+    })
+}
+
+/// Accept connections until the authorization redirect arrives, answering
+/// each with a short page, and return the authorization code.
+async fn receive_code(listener: &TcpListener, expected_state: &str) -> Result<String> {
+    loop {
+        let (mut stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| Error::OAuth(format!("failed to accept callback: {}", e)))?;
+
+        let mut buf = vec![0; 8192];
+        let n = stream.read(&mut buf).await.unwrap_or(0);
+        let request = String::from_utf8_lossy(&buf[..n]);
+        let Some(path) = request.lines().next().and_then(|l| l.split(' ').nth(1)) else {
+            continue;
+        };
+        let Ok(url) = url::Url::parse(&format!("http://localhost{}", path)) else {
+            continue;
+        };
+        if url.path() != "/callback" {
+            let _ = respond(&mut stream, "404 Not Found", "Not found").await;
+            continue;
+        }
+
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.into_owned())
+        };
+
+        let result = if let Some(error) = param("error") {
+            Err(Error::OAuth(format!(
+                "authorization denied: {}",
+                param("error_description").unwrap_or(error)
+            )))
+        } else if param("state").as_deref() != Some(expected_state) {
+            Err(Error::OAuth(
+                "state mismatch in authorization callback".to_string(),
+            ))
+        } else {
+            param("code")
+                .ok_or_else(|| Error::OAuth("authorization callback had no code".to_string()))
+        };
+
+        let message = match &result {
+            Ok(_) => "Authorization complete. You can close this window.",
+            Err(_) => "Authorization failed. Check your terminal for details.",
+        };
+        let _ = respond(&mut stream, "200 OK", message).await;
+        return result;
+    }
+}
+
+async fn respond(
+    stream: &mut tokio::net::TcpStream,
+    status: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await
+}
+
+/// Best-effort attempt to open `url` in the default browser; the URL is
+/// always printed as well.
+fn open_browser(url: &str) {
+    use std::process::{Command, Stdio};
+
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        // Not `cmd /C start`, which splits the URL at its `&`s
+        let mut command = Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    let _ = command
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+}
+
+/// A URL-safe random string encoding `bytes` random bytes.
+fn random_token(bytes: usize) -> Result<String> {
+    let mut buf = vec![0; bytes];
+    getrandom::getrandom(&mut buf)
+        .map_err(|e| Error::OAuth(format!("failed to generate random state: {}", e)))?;
+    Ok(URL_SAFE_NO_PAD.encode(buf))
+}
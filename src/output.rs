@@ -1,42 +1,387 @@
-use crate::commands::comment::Comment;
-use crate::commands::cycle::{Cycle, CycleDetail};
-use crate::commands::document::{Document, DocumentDetail};
-use crate::commands::issue::{Issue, IssueDetail};
-use crate::commands::label::Label;
-use crate::commands::project::{Project, ProjectDetail};
-use crate::commands::search::SearchResult;
-use crate::commands::team::Team;
-use crate::commands::workflow::WorkflowState;
+use crate::cache::EntryStatus;
+use crate::client::{RateLimitBudget, RateLimitStatus};
+use crate::commands::auth::AuthStatus;
+use crate::commands::config::{ConfigEntry, ProfileEntry};
+use crate::commands::issue::IssueChanges;
+use crate::queries::comment::Comment;
+use crate::queries::cycle::{Cycle, CycleDetail};
+use crate::queries::document::{CreatedDocument, Document, DocumentDetail};
+use crate::queries::issue::{CreatedIssue, Issue, IssueDetail, Priority};
+use crate::queries::label::Label;
+use crate::queries::project::{CreatedProject, Project, ProjectDetail};
+use crate::queries::search::SearchResult;
+use crate::queries::team::Team;
+use crate::queries::user::User;
+use crate::queries::workflow::WorkflowState;
+use crate::store::SyncSummary;
+use clap::ValueEnum;
 use owo_colors::{OwoColorize, Stream, Style};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
 use tabled::{Table, Tabled};
 
-pub fn print_user(name: &str, email: &str, id: &str) {
+/// How command results are written to stdout.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// Human-readable tables and detail views
+    #[default]
+    Table,
+    /// Pretty-printed JSON
+    Json,
+    /// One JSON object per line
+    Ndjson,
+    /// YAML
+    Yaml,
+    /// Comma-separated values with a header row
+    Csv,
+    /// Tab-separated values with a header row
+    Tsv,
+}
+
+static FORMAT: OnceLock<Format> = OnceLock::new();
+
+/// Select the output format for the rest of the process. Only the first call
+/// takes effect.
+pub fn set_format(format: Format) {
+    let _ = FORMAT.set(format);
+}
+
+pub fn format() -> Format {
+    FORMAT.get().copied().unwrap_or_default()
+}
+
+/// Whether lists are written one object per line, so each page of a
+/// paginated list can be printed as soon as it arrives.
+pub fn streams_lists() -> bool {
+    format() == Format::Ndjson
+}
+
+/// Write one page of a list streamed as ndjson.
+pub fn print_page<T: Serialize>(items: &[T]) {
+    for item in items {
+        println!("{}", to_value(item));
+    }
+}
+
+/// Emit `items` in the active structured format. Returns `false` when the
+/// format is `table`, leaving rendering to the caller.
+fn emit_list<T: Serialize>(items: &[T]) -> bool {
+    let values: Vec<serde_json::Value> = items.iter().map(to_value).collect();
+    match format() {
+        Format::Table => return false,
+        Format::Json => println!("{}", to_pretty_json(&values)),
+        Format::Ndjson => {
+            for value in &values {
+                println!("{}", value);
+            }
+        }
+        Format::Yaml => print!("{}", to_yaml(&values)),
+        Format::Csv => print!("{}", to_delimited(&values, ',')),
+        Format::Tsv => print!("{}", to_delimited(&values, '\t')),
+    }
+    true
+}
+
+/// Emit a single object in the active structured format. Returns `false`
+/// when the format is `table`.
+fn emit<T: Serialize>(item: &T) -> bool {
+    let value = to_value(item);
+    match format() {
+        Format::Table => return false,
+        Format::Json => println!("{}", to_pretty_json(&value)),
+        Format::Ndjson => println!("{}", value),
+        Format::Yaml => print!("{}", to_yaml(&value)),
+        Format::Csv => print!("{}", to_delimited(&[value], ',')),
+        Format::Tsv => print!("{}", to_delimited(&[value], '\t')),
+    }
+    true
+}
+
+fn to_value<T: Serialize>(item: &T) -> serde_json::Value {
+    serde_json::to_value(item).expect("output types serialize to JSON")
+}
+
+fn to_pretty_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value).expect("output types serialize to JSON")
+}
+
+fn to_yaml<T: Serialize>(value: &T) -> String {
+    serde_yaml::to_string(value).expect("output types serialize to YAML")
+}
+
+/// Render objects as delimited rows. Nested objects are flattened into
+/// dotted column names (`state.name`); arrays are written as JSON.
+fn to_delimited(values: &[serde_json::Value], delimiter: char) -> String {
+    let rows: Vec<Vec<(String, String)>> = values
+        .iter()
+        .map(|value| {
+            let mut cells = Vec::new();
+            flatten("", value, &mut cells);
+            cells
+        })
+        .collect();
+
+    let mut columns: Vec<&str> = Vec::new();
+    for (key, _) in rows.iter().flatten() {
+        add_column(&mut columns, key);
+    }
+
+    let mut out = String::new();
+    let header: Vec<String> = columns.iter().map(|c| quote(c, delimiter)).collect();
+    out.push_str(&header.join(&delimiter.to_string()));
+    out.push('\n');
+    for row in &rows {
+        let line: Vec<String> = columns
+            .iter()
+            .map(|column| {
+                row.iter()
+                    .find(|(key, _)| key == column)
+                    .map(|(_, cell)| quote(cell, delimiter))
+                    .unwrap_or_default()
+            })
+            .collect();
+        out.push_str(&line.join(&delimiter.to_string()));
+        out.push('\n');
+    }
+    out
+}
+
+/// Add `key` to the header unless it is already there. A null nested object
+/// only gets a column of its own until another row fills in its fields,
+/// whose columns then take its place, so the header doesn't depend on
+/// whether the first rows happen to be null.
+fn add_column<'a>(columns: &mut Vec<&'a str>, key: &'a str) {
+    let child_of = |parent: &str, column: &str| {
+        column
+            .strip_prefix(parent)
+            .is_some_and(|rest| rest.starts_with('.'))
+    };
+    if columns.contains(&key) || columns.iter().any(|column| child_of(key, column)) {
+        return;
+    }
+    let mut parent = key;
+    while let Some((prefix, _)) = parent.rsplit_once('.') {
+        parent = prefix;
+        if let Some(i) = columns.iter().position(|column| *column == parent) {
+            columns[i] = key;
+            return;
+        }
+        if let Some(i) = columns.iter().rposition(|column| child_of(parent, column)) {
+            columns.insert(i + 1, key);
+            return;
+        }
+    }
+    columns.push(key);
+}
+
+fn flatten(prefix: &str, value: &serde_json::Value, cells: &mut Vec<(String, String)>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&key, value, cells);
+            }
+        }
+        serde_json::Value::Null => cells.push((prefix.to_string(), String::new())),
+        serde_json::Value::String(s) => cells.push((prefix.to_string(), s.clone())),
+        other => cells.push((prefix.to_string(), other.to_string())),
+    }
+}
+
+fn quote(cell: &str, delimiter: char) -> String {
+    if cell.contains(delimiter) || cell.contains('"') || cell.contains('\n') {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+pub fn print_user(user: &User) {
+    if emit(user) {
+        return;
+    }
+
     println!(
         "{}: {}",
         "Name".if_supports_color(Stream::Stdout, |s| s.bold()),
-        name
+        user.name
     );
     println!(
         "{}: {}",
         "Email".if_supports_color(Stream::Stdout, |s| s.bold()),
-        email
+        user.email
+    );
+    println!(
+        "{}: {}",
+        "Workspace".if_supports_color(Stream::Stdout, |s| s.bold()),
+        user.organization.name
     );
     let id_style = Style::new().bold().dimmed();
     let dimmed_style = Style::new().dimmed();
     println!(
         "{}: {}",
         "ID".if_supports_color(Stream::Stdout, |s| s.style(id_style)),
-        id.if_supports_color(Stream::Stdout, |s| s.style(dimmed_style))
+        user.id
+            .inner()
+            .if_supports_color(Stream::Stdout, |s| s.style(dimmed_style))
     );
 }
 
+pub fn print_auth_status(status: &AuthStatus) {
+    if emit(status) {
+        return;
+    }
+
+    println!(
+        "Logged in to {} as {} ({})",
+        status
+            .user
+            .organization
+            .name
+            .if_supports_color(Stream::Stdout, |s| s.bold()),
+        status
+            .user
+            .name
+            .if_supports_color(Stream::Stdout, |s| s.bold()),
+        status.user.email
+    );
+    println!(
+        "{}: {}",
+        "Profile".if_supports_color(Stream::Stdout, |s| s.dimmed()),
+        status.profile
+    );
+    println!(
+        "{}: {}",
+        "Source".if_supports_color(Stream::Stdout, |s| s.dimmed()),
+        status.source
+    );
+    println!(
+        "{}: {}",
+        "Credential".if_supports_color(Stream::Stdout, |s| s.dimmed()),
+        status.credential
+    );
+}
+
+pub fn print_rate_limit(status: &RateLimitStatus) {
+    if emit(status) {
+        return;
+    }
+
+    if status.requests.is_none() && status.complexity.is_none() {
+        println!("The API did not report any rate limits.");
+        return;
+    }
+
+    let budget = |label: &str, budget: &Option<RateLimitBudget>| {
+        let Some(budget) = budget else {
+            return;
+        };
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let seconds = budget.reset.saturating_sub(now).div_ceil(1000);
+        let reset = if seconds < 60 {
+            format!("{}s", seconds)
+        } else {
+            format!("{} min", seconds.div_ceil(60))
+        };
+        println!(
+            "{}: {} of {} remaining, resets in {}",
+            label.if_supports_color(Stream::Stdout, |s| s.dimmed()),
+            budget
+                .remaining
+                .if_supports_color(Stream::Stdout, |s| s.bold()),
+            budget.limit,
+            reset
+        );
+    };
+    budget("Requests", &status.requests);
+    budget("Complexity", &status.complexity);
+    if let Some(cost) = status.last_complexity {
+        println!(
+            "{}: {}",
+            "Last query complexity".if_supports_color(Stream::Stdout, |s| s.dimmed()),
+            cost
+        );
+    }
+}
+
+/// Report a failed command on stderr. With `--format json` or `ndjson` the
+/// error is a JSON object, so wrappers can tell its kind and exit code apart
+/// without parsing the message.
 pub fn print_error(error: &crate::error::Error) {
+    if matches!(format(), Format::Json | Format::Ndjson) {
+        let value = serde_json::json!({
+            "error": {
+                "kind": error.kind(),
+                "message": error.to_string(),
+                "hint": error.hint(),
+                "exitCode": error.exit_code(),
+            }
+        });
+        match format() {
+            Format::Json => eprintln!("{}", to_pretty_json(&value)),
+            _ => eprintln!("{}", value),
+        }
+        return;
+    }
+
     let error_style = Style::new().red().bold();
     eprintln!(
         "{}: {}",
         "Error".if_supports_color(Stream::Stderr, |s| s.style(error_style)),
         error
     );
+    if let Some(hint) = error.hint() {
+        eprintln!(
+            "{}: {}",
+            "hint".if_supports_color(Stream::Stderr, |s| s.cyan()),
+            hint
+        );
+    }
+}
+
+/// Say that a newer release than the running one is out.
+pub fn print_update_notice(current: &str, latest: &str) {
+    eprintln!(
+        "{} v{} -> {} (run `linear update` to install it)",
+        "A new version of linear is available:".if_supports_color(Stream::Stderr, |s| s.yellow()),
+        current,
+        latest
+    );
+}
+
+/// Report something that went wrong without failing the command, such as a
+/// field the API could not return.
+pub fn print_warning(message: &str) {
+    let warning_style = Style::new().yellow().bold();
+    eprintln!(
+        "{}: {}",
+        "Warning".if_supports_color(Stream::Stderr, |s| s.style(warning_style)),
+        message
+    );
+}
+
+/// The fields an interactive command is about to send, shown before it asks
+/// to go ahead.
+pub fn print_summary(rows: &[(&str, String)]) {
+    let width = rows.iter().map(|(field, _)| field.len()).max().unwrap_or(0);
+    eprintln!();
+    for (field, value) in rows {
+        let field = format!("{:width$}", field, width = width);
+        eprintln!(
+            "  {}  {}",
+            field.if_supports_color(Stream::Stderr, |s| s.dimmed()),
+            value
+        );
+    }
+    eprintln!();
 }
 
 #[derive(Tabled)]
@@ -54,6 +399,10 @@ struct IssueRow {
 }
 
 pub fn print_issues(issues: &[Issue]) {
+    if emit_list(issues) {
+        return;
+    }
+
     if issues.is_empty() {
         println!("No issues found.");
         return;
@@ -94,8 +443,8 @@ fn truncate(s: &str, max: usize) -> String {
     }
 }
 
-fn priority_label(p: i32) -> String {
-    match p {
+pub fn priority_label(p: Priority) -> String {
+    match p.0 {
         0 => "None".to_string(),
         1 => "Urgent".to_string(),
         2 => "High".to_string(),
@@ -106,6 +455,10 @@ fn priority_label(p: i32) -> String {
 }
 
 pub fn print_issue_detail(issue: &IssueDetail) {
+    if emit(issue) {
+        return;
+    }
+
     let id_style = Style::new().cyan().bold();
     let title_style = Style::new().bold();
     println!(
@@ -167,7 +520,45 @@ pub fn print_issue_detail(issue: &IssueDetail) {
     }
 }
 
+pub fn print_created_issue(issue: &CreatedIssue) {
+    if emit(issue) {
+        return;
+    }
+
+    println!("Created {} - {}", issue.identifier, issue.title);
+    println!("{}", issue.url);
+}
+
+pub fn print_issue_changes(issue: &IssueChanges) {
+    if emit(issue) {
+        return;
+    }
+
+    if issue.changes.is_empty() {
+        println!("No changes to {} - {}", issue.identifier, issue.title);
+        return;
+    }
+
+    println!("Updated {} - {}", issue.identifier, issue.title);
+    let value = |value: &Option<String>| match value {
+        Some(value) => truncate(value.lines().next().unwrap_or_default(), 40),
+        None => "—".to_string(),
+    };
+    for change in &issue.changes {
+        println!(
+            "  {:<12} {} → {}",
+            change.field.if_supports_color(Stream::Stdout, |s| s.bold()),
+            value(&change.before).if_supports_color(Stream::Stdout, |s| s.dimmed()),
+            value(&change.after)
+        );
+    }
+}
+
 pub fn print_teams(teams: &[Team]) {
+    if emit_list(teams) {
+        return;
+    }
+
     if teams.is_empty() {
         println!("No teams found.");
         return;
@@ -185,6 +576,10 @@ pub fn print_teams(teams: &[Team]) {
 }
 
 pub fn print_team_detail(team: &Team) {
+    if emit(team) {
+        return;
+    }
+
     let key_style = Style::new().cyan().bold();
     let name_style = Style::new().bold();
     println!(
@@ -204,6 +599,10 @@ pub fn print_team_detail(team: &Team) {
 }
 
 pub fn print_projects(projects: &[Project]) {
+    if emit_list(projects) {
+        return;
+    }
+
     if projects.is_empty() {
         println!("No projects found.");
         return;
@@ -221,6 +620,10 @@ pub fn print_projects(projects: &[Project]) {
 }
 
 pub fn print_project_detail(project: &ProjectDetail) {
+    if emit(project) {
+        return;
+    }
+
     println!(
         "{}",
         project.name.if_supports_color(Stream::Stdout, |s| s.bold())
@@ -260,7 +663,20 @@ pub fn print_project_detail(project: &ProjectDetail) {
     }
 }
 
+pub fn print_created_project(project: &CreatedProject) {
+    if emit(project) {
+        return;
+    }
+
+    println!("Created project: {}", project.name);
+    println!("{}", project.url);
+}
+
 pub fn print_cycles(cycles: &[Cycle]) {
+    if emit_list(cycles) {
+        return;
+    }
+
     if cycles.is_empty() {
         println!("No cycles found.");
         return;
@@ -283,6 +699,10 @@ pub fn print_cycles(cycles: &[Cycle]) {
 }
 
 pub fn print_cycle_detail(cycle: &CycleDetail) {
+    if emit(cycle) {
+        return;
+    }
+
     let number_style = Style::new().cyan().bold();
     let name_style = Style::new().bold();
     let name = cycle.name.as_deref().unwrap_or("");
@@ -314,6 +734,10 @@ pub fn print_cycle_detail(cycle: &CycleDetail) {
 }
 
 pub fn print_labels(labels: &[Label]) {
+    if emit_list(labels) {
+        return;
+    }
+
     if labels.is_empty() {
         println!("No labels found.");
         return;
@@ -329,6 +753,10 @@ pub fn print_labels(labels: &[Label]) {
 }
 
 pub fn print_workflow_states(states: &[WorkflowState]) {
+    if emit_list(states) {
+        return;
+    }
+
     if states.is_empty() {
         println!("No workflow states found.");
         return;
@@ -357,6 +785,10 @@ pub fn print_workflow_states(states: &[WorkflowState]) {
 }
 
 pub fn print_comments(comments: &[Comment]) {
+    if emit_list(comments) {
+        return;
+    }
+
     if comments.is_empty() {
         println!("No comments found.");
         return;
@@ -379,7 +811,19 @@ pub fn print_comments(comments: &[Comment]) {
     }
 }
 
+pub fn print_created_comment(comment: &Comment) {
+    if emit(comment) {
+        return;
+    }
+
+    println!("Comment added.");
+}
+
 pub fn print_documents(documents: &[Document]) {
+    if emit_list(documents) {
+        return;
+    }
+
     if documents.is_empty() {
         println!("No documents found.");
         return;
@@ -395,6 +839,10 @@ pub fn print_documents(documents: &[Document]) {
 }
 
 pub fn print_document_detail(doc: &DocumentDetail) {
+    if emit(doc) {
+        return;
+    }
+
     println!(
         "{}",
         doc.title.if_supports_color(Stream::Stdout, |s| s.bold())
@@ -419,7 +867,20 @@ pub fn print_document_detail(doc: &DocumentDetail) {
     }
 }
 
+pub fn print_created_document(doc: &CreatedDocument) {
+    if emit(doc) {
+        return;
+    }
+
+    println!("Created document: {}", doc.title);
+    println!("ID: {}", doc.id.inner());
+}
+
 pub fn print_search_results(results: &[SearchResult]) {
+    if emit_list(results) {
+        return;
+    }
+
     if results.is_empty() {
         println!("No results found.");
         return;
@@ -442,3 +903,94 @@ pub fn print_search_results(results: &[SearchResult]) {
         );
     }
 }
+
+pub fn print_config(entries: &[ConfigEntry]) {
+    if emit_list(entries) {
+        return;
+    }
+
+    for entry in entries {
+        if let Some(value) = &entry.value {
+            println!(
+                "{} = {}",
+                entry.key.if_supports_color(Stream::Stdout, |s| s.bold()),
+                value
+            );
+        }
+    }
+}
+
+pub fn print_profiles(profiles: &[ProfileEntry]) {
+    if emit_list(profiles) {
+        return;
+    }
+
+    for profile in profiles {
+        if profile.active {
+            println!(
+                "* {}",
+                profile.name.if_supports_color(Stream::Stdout, |s| s.bold())
+            );
+        } else {
+            println!("  {}", profile.name);
+        }
+    }
+}
+
+pub fn print_cache_status(dir: &std::path::Path, entries: &[EntryStatus]) {
+    if emit_list(entries) {
+        return;
+    }
+
+    if entries.is_empty() {
+        println!("Nothing cached in {}", dir.display());
+        return;
+    }
+
+    println!(
+        "{} {}",
+        "Cache:".if_supports_color(Stream::Stdout, |s| s.dimmed()),
+        dir.display()
+    );
+    for entry in entries {
+        let age = match entry.age {
+            s if s < 60 => format!("{}s", s),
+            s if s < 3600 => format!("{} min", s / 60),
+            s => format!("{} h", s / 3600),
+        };
+        let state = if entry.fresh { "" } else { " (expired)" };
+        println!(
+            "  {:<24} {:>5} items, fetched {} ago{}",
+            entry.key.if_supports_color(Stream::Stdout, |s| s.bold()),
+            entry.items,
+            age,
+            state
+        );
+    }
+}
+
+pub fn print_sync_summary(path: &std::path::Path, summary: &[SyncSummary]) {
+    if emit_list(summary) {
+        return;
+    }
+
+    println!(
+        "{} {}",
+        "Synced to".if_supports_color(Stream::Stdout, |s| s.dimmed()),
+        path.display()
+    );
+    for entity in summary {
+        println!(
+            "  {:<10} {:>6} updated{}",
+            entity
+                .entity
+                .if_supports_color(Stream::Stdout, |s| s.bold()),
+            entity.synced,
+            entity
+                .high_water
+                .as_deref()
+                .map(|at| format!(", changes up to {}", at))
+                .unwrap_or_default()
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! Interactive prompts for arguments left out on the command line.
+//!
+//! In a terminal, commands that create things ask for missing required
+//! arguments, and for the optional fields usually set alongside them, then
+//! show what they are about to send and ask for confirmation. Lists are
+//! fuzzy-filterable as you type. Anywhere else (see `input::interactive`) a
+//! missing argument stays an error, so scripts fail fast instead of waiting
+//! on a prompt.
+
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, FuzzySelect, Input, MultiSelect};
+
+use crate::error::{Error, Result};
+use crate::output;
+
+/// Ask for a line of text, which may not be empty.
+pub fn text(prompt: &str) -> Result<String> {
+    Input::<String>::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .interact_text()
+        .map(|text| text.trim().to_string())
+        .map_err(terminal)
+}
+
+/// Pick one of `items`, returning its index.
+pub fn select<T>(prompt: &str, items: &[T], label: impl Fn(&T) -> String) -> Result<usize> {
+    if items.is_empty() {
+        return Err(Error::NotFound(format!(
+            "nothing to choose from for {}",
+            prompt.to_lowercase()
+        )));
+    }
+    let labels: Vec<String> = items.iter().map(label).collect();
+    FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(&labels)
+        .default(0)
+        .interact_opt()
+        .map_err(terminal)?
+        .ok_or_else(aborted)
+}
+
+/// Pick one of `items` or `none`, listed first, returning the index picked.
+pub fn select_optional<T>(
+    prompt: &str,
+    none: &str,
+    items: &[T],
+    label: impl Fn(&T) -> String,
+) -> Result<Option<usize>> {
+    let labels: Vec<String> = std::iter::once(none.to_string())
+        .chain(items.iter().map(label))
+        .collect();
+    let index = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(&labels)
+        .default(0)
+        .interact_opt()
+        .map_err(terminal)?
+        .ok_or_else(aborted)?;
+    Ok(index.checked_sub(1))
+}
+
+/// Pick any number of `items`, returning their indices.
+pub fn select_many<T>(
+    prompt: &str,
+    items: &[T],
+    label: impl Fn(&T) -> String,
+) -> Result<Vec<usize>> {
+    let labels: Vec<String> = items.iter().map(label).collect();
+    MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("{} (space to select, enter to continue)", prompt))
+        .items(&labels)
+        .interact_opt()
+        .map_err(terminal)?
+        .ok_or_else(aborted)
+}
+
+/// Ask a yes/no question.
+pub fn confirm(prompt: &str, default: bool) -> Result<bool> {
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(default)
+        .interact_opt()
+        .map_err(terminal)?
+        .ok_or_else(aborted)
+}
+
+/// Show what is about to be sent and ask to go ahead.
+pub fn confirm_summary(prompt: &str, rows: &[(&str, String)]) -> Result<()> {
+    output::print_summary(rows);
+    if confirm(prompt, true)? {
+        Ok(())
+    } else {
+        Err(aborted())
+    }
+}
+
+/// How long a piece of text is, for a summary row.
+pub fn lines(text: Option<&str>) -> String {
+    match text.map_or(0, |text| text.lines().count()) {
+        0 => "None".to_string(),
+        1 => "1 line".to_string(),
+        n => format!("{} lines", n),
+    }
+}
+
+fn aborted() -> Error {
+    Error::InvalidInput("aborted".to_string())
+}
+
+fn terminal(error: dialoguer::Error) -> Error {
+    let dialoguer::Error::IO(error) = error;
+    Error::io("failed to read from the terminal", error)
+}
@@ -0,0 +1,59 @@
+use serde::Serialize;
+
+use super::{connection, IdPageVariablesFields};
+use crate::generated::schema;
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "IdPageVariables")]
+pub struct IssueCommentsQuery {
+    #[arguments(id: $id)]
+    pub issue: IssueWithComments,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Issue", variables = "IdPageVariables")]
+pub struct IssueWithComments {
+    #[arguments(first: $first, after: $after)]
+    pub comments: CommentConnection,
+}
+
+connection!(CommentConnection, "CommentConnection", Comment);
+
+#[derive(cynic::QueryFragment, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub id: cynic::Id,
+    pub body: String,
+    pub created_at: String,
+    pub user: Option<CommentUser>,
+}
+
+#[derive(cynic::QueryFragment, Serialize, Clone)]
+#[cynic(graphql_type = "User")]
+pub struct CommentUser {
+    pub name: String,
+}
+
+#[derive(cynic::QueryVariables)]
+pub struct CreateCommentVariables {
+    pub input: CommentCreateInput,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Mutation", variables = "CreateCommentVariables")]
+pub struct CreateCommentMutation {
+    #[arguments(input: $input)]
+    pub comment_create: CommentPayload,
+}
+
+#[derive(cynic::QueryFragment)]
+pub struct CommentPayload {
+    pub success: bool,
+    pub comment: Option<Comment>,
+}
+
+#[derive(cynic::InputObject)]
+pub struct CommentCreateInput {
+    pub issue_id: String,
+    pub body: String,
+}
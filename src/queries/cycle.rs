@@ -0,0 +1,67 @@
+use serde::Serialize;
+
+use super::{
+    connection, BooleanComparator, DateComparator, IdVariablesFields, NumberComparator, TeamFilter,
+};
+use crate::generated::schema;
+
+#[derive(cynic::QueryVariables)]
+pub struct CyclesVariables {
+    pub first: i32,
+    pub after: Option<String>,
+    pub filter: CycleFilter,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "CyclesVariables")]
+pub struct CyclesQuery {
+    #[arguments(first: $first, after: $after, filter: $filter)]
+    pub cycles: CycleConnection,
+}
+
+connection!(CycleConnection, "CycleConnection", Cycle);
+
+#[derive(cynic::QueryFragment, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Cycle {
+    pub id: cynic::Id,
+    pub number: f64,
+    pub name: Option<String>,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub progress: f64,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "IdVariables")]
+pub struct CycleQuery {
+    #[arguments(id: $id)]
+    pub cycle: CycleDetail,
+}
+
+#[derive(cynic::QueryFragment, Serialize)]
+#[cynic(graphql_type = "Cycle")]
+#[serde(rename_all = "camelCase")]
+pub struct CycleDetail {
+    pub id: cynic::Id,
+    pub number: f64,
+    pub name: Option<String>,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub progress: f64,
+    pub description: Option<String>,
+}
+
+#[derive(cynic::InputObject, Default)]
+pub struct CycleFilter {
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub team: Option<TeamFilter>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub number: Option<NumberComparator>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub is_active: Option<BooleanComparator>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub is_next: Option<BooleanComparator>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateComparator>,
+}
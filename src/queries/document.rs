@@ -0,0 +1,86 @@
+use serde::Serialize;
+
+use super::{connection, project::ProjectFilter, IdVariablesFields};
+use crate::generated::schema;
+
+#[derive(cynic::QueryVariables)]
+pub struct DocumentsVariables {
+    pub first: i32,
+    pub after: Option<String>,
+    pub filter: DocumentFilter,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "DocumentsVariables")]
+pub struct DocumentsQuery {
+    #[arguments(first: $first, after: $after, filter: $filter)]
+    pub documents: DocumentConnection,
+}
+
+connection!(DocumentConnection, "DocumentConnection", Document);
+
+#[derive(cynic::QueryFragment, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Document {
+    pub id: cynic::Id,
+    pub title: String,
+    pub updated_at: String,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "IdVariables")]
+pub struct DocumentQuery {
+    #[arguments(id: $id)]
+    pub document: DocumentDetail,
+}
+
+#[derive(cynic::QueryFragment, Serialize)]
+#[cynic(graphql_type = "Document")]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentDetail {
+    pub id: cynic::Id,
+    pub title: String,
+    pub content: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(cynic::InputObject, Default)]
+pub struct DocumentFilter {
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub project: Option<ProjectFilter>,
+}
+
+#[derive(cynic::QueryVariables)]
+pub struct CreateDocumentVariables {
+    pub input: DocumentCreateInput,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Mutation", variables = "CreateDocumentVariables")]
+pub struct CreateDocumentMutation {
+    #[arguments(input: $input)]
+    pub document_create: DocumentPayload,
+}
+
+#[derive(cynic::QueryFragment)]
+pub struct DocumentPayload {
+    pub success: bool,
+    pub document: Option<CreatedDocument>,
+}
+
+#[derive(cynic::QueryFragment, Serialize)]
+#[cynic(graphql_type = "Document")]
+pub struct CreatedDocument {
+    pub id: cynic::Id,
+    pub title: String,
+    pub url: String,
+}
+
+#[derive(cynic::InputObject)]
+pub struct DocumentCreateInput {
+    pub title: String,
+    pub project_id: String,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
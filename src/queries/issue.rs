@@ -0,0 +1,301 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::resolve::TeamRef;
+use super::{
+    connection, BooleanComparator, DateComparator, IdVariablesFields, Patch, StringComparator,
+    TeamFilter,
+};
+use crate::generated::schema;
+
+#[derive(cynic::QueryVariables)]
+pub struct IssuesVariables {
+    pub first: i32,
+    pub after: Option<String>,
+    pub filter: IssueFilter,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "IssuesVariables")]
+pub struct IssuesQuery {
+    #[arguments(first: $first, after: $after, filter: $filter)]
+    pub issues: IssueConnection,
+}
+
+connection!(IssueConnection, "IssueConnection", Issue);
+
+#[derive(cynic::QueryFragment, Serialize)]
+pub struct Issue {
+    pub identifier: String,
+    pub title: String,
+    pub state: Option<IssueState>,
+    pub assignee: Option<Assignee>,
+    pub priority: Priority,
+}
+
+/// An issue's priority, from 0 (none) to 4 (low). The schema types it as a
+/// `Float`, but it is always a whole number and is output as one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Priority(pub i32);
+
+impl From<f64> for Priority {
+    fn from(priority: f64) -> Self {
+        Priority(priority as i32)
+    }
+}
+
+impl<'de> Deserialize<'de> for Priority {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f64::deserialize(deserializer).map(Priority::from)
+    }
+}
+
+cynic::impl_scalar!(Priority, schema::Float);
+
+#[derive(cynic::QueryFragment, Serialize)]
+#[cynic(graphql_type = "WorkflowState")]
+pub struct IssueState {
+    pub name: String,
+}
+
+#[derive(cynic::QueryFragment, Serialize)]
+#[cynic(graphql_type = "User")]
+pub struct Assignee {
+    pub name: String,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "IdVariables")]
+pub struct IssueQuery {
+    #[arguments(id: $id)]
+    pub issue: IssueDetail,
+}
+
+#[derive(cynic::QueryFragment, Serialize)]
+#[cynic(graphql_type = "Issue")]
+#[serde(rename_all = "camelCase")]
+pub struct IssueDetail {
+    pub identifier: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub state: Option<IssueState>,
+    pub assignee: Option<Assignee>,
+    pub priority: Priority,
+    pub created_at: String,
+    pub updated_at: String,
+    pub team: Team,
+}
+
+#[derive(cynic::QueryFragment, Serialize)]
+pub struct Team {
+    pub key: String,
+    pub name: String,
+}
+
+#[derive(cynic::InputObject, Default)]
+pub struct IssueFilter {
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub team: Option<TeamFilter>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub state: Option<WorkflowStateFilter>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<NullableUserFilter>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateComparator>,
+}
+
+#[derive(cynic::InputObject)]
+pub struct WorkflowStateFilter {
+    pub name: StringComparator,
+}
+
+#[derive(cynic::InputObject, Default)]
+pub struct NullableUserFilter {
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub is_me: Option<BooleanComparator>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub name: Option<StringComparator>,
+}
+
+#[derive(cynic::QueryVariables)]
+pub struct CreateIssueVariables {
+    pub input: IssueCreateInput,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Mutation", variables = "CreateIssueVariables")]
+pub struct CreateIssueMutation {
+    #[arguments(input: $input)]
+    pub issue_create: IssueCreatePayload,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "IssuePayload")]
+pub struct IssueCreatePayload {
+    pub success: bool,
+    pub issue: Option<CreatedIssue>,
+}
+
+#[derive(cynic::QueryFragment, Serialize)]
+#[cynic(graphql_type = "Issue")]
+pub struct CreatedIssue {
+    pub id: cynic::Id,
+    pub identifier: String,
+    pub title: String,
+    pub url: String,
+}
+
+#[derive(cynic::InputObject, Default)]
+pub struct IssueCreateInput {
+    pub title: String,
+    pub team_id: String,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub assignee_id: Option<String>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub state_id: Option<String>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub label_ids: Option<Vec<String>>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub project_milestone_id: Option<String>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub cycle_id: Option<String>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<i32>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<String>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub subscriber_ids: Option<Vec<String>>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub template_id: Option<String>,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "IdVariables")]
+pub struct IssueFieldsQuery {
+    #[arguments(id: $id)]
+    pub issue: UpdatedIssue,
+}
+
+#[derive(cynic::QueryVariables)]
+pub struct UpdateIssueVariables {
+    pub id: String,
+    pub input: IssueUpdateInput,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Mutation", variables = "UpdateIssueVariables")]
+pub struct UpdateIssueMutation {
+    #[arguments(id: $id, input: $input)]
+    pub issue_update: IssueUpdatePayload,
+}
+
+#[derive(cynic::QueryVariables)]
+pub struct IssueLabelVariables {
+    pub id: String,
+    pub label_id: String,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Mutation", variables = "IssueLabelVariables")]
+pub struct AddLabelMutation {
+    #[arguments(id: $id, labelId: $label_id)]
+    pub issue_add_label: IssueUpdatePayload,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Mutation", variables = "IssueLabelVariables")]
+pub struct RemoveLabelMutation {
+    #[arguments(id: $id, labelId: $label_id)]
+    pub issue_remove_label: IssueUpdatePayload,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "IssuePayload")]
+pub struct IssueUpdatePayload {
+    pub success: bool,
+    pub issue: Option<UpdatedIssue>,
+}
+
+/// The fields `issue update` can change, read before and after an update
+/// to show what changed.
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Issue")]
+pub struct UpdatedIssue {
+    pub id: cynic::Id,
+    pub identifier: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub state: Option<IssueState>,
+    pub assignee: Option<Assignee>,
+    pub priority: Priority,
+    pub labels: IssueLabelConnection,
+    pub project: Option<IssueProject>,
+    pub cycle: Option<IssueCycle>,
+    pub estimate: Option<f64>,
+    pub due_date: Option<String>,
+    pub parent: Option<ParentIssue>,
+    pub team: TeamRef,
+}
+
+#[derive(cynic::QueryFragment)]
+pub struct IssueLabelConnection {
+    pub nodes: Vec<IssueLabel>,
+}
+
+#[derive(cynic::QueryFragment)]
+pub struct IssueLabel {
+    pub name: String,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Project")]
+pub struct IssueProject {
+    pub name: String,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Cycle")]
+pub struct IssueCycle {
+    pub number: f64,
+    pub name: Option<String>,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Issue")]
+pub struct ParentIssue {
+    pub identifier: String,
+}
+
+#[derive(cynic::InputObject, Default)]
+pub struct IssueUpdateInput {
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub state_id: Option<String>,
+    #[cynic(skip_serializing_if = "Patch::is_keep")]
+    pub assignee_id: Patch<String>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub cycle_id: Option<String>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<i32>,
+    #[cynic(skip_serializing_if = "Patch::is_keep")]
+    pub due_date: Patch<String>,
+    #[cynic(skip_serializing_if = "Patch::is_keep")]
+    pub parent_id: Patch<String>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub team_id: Option<String>,
+}
@@ -0,0 +1,31 @@
+use serde::Serialize;
+
+use super::{connection, PageVariablesFields};
+use crate::generated::schema;
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "PageVariables")]
+pub struct LabelsQuery {
+    #[arguments(first: $first, after: $after)]
+    pub issue_labels: IssueLabelConnection,
+}
+
+connection!(IssueLabelConnection, "IssueLabelConnection", Label);
+
+/// A label, which belongs to a team or, when `team` is unset, the whole
+/// workspace.
+#[derive(cynic::QueryFragment, Serialize, Clone)]
+#[cynic(graphql_type = "IssueLabel")]
+pub struct Label {
+    pub id: cynic::Id,
+    pub name: String,
+    pub color: String,
+    pub team: Option<LabelTeam>,
+}
+
+#[derive(cynic::QueryFragment, Serialize, Clone)]
+#[cynic(graphql_type = "Team")]
+pub struct LabelTeam {
+    pub id: cynic::Id,
+    pub key: String,
+}
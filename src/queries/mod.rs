@@ -0,0 +1,181 @@
+//! Typed GraphQL operations, checked against `schema.graphql` at compile
+//! time. Each module holds the fragments for one area of the API; the
+//! command modules build and run them through [`LinearClient::run`] and
+//! [`LinearClient::paginate`].
+//!
+//! [`LinearClient::run`]: crate::client::LinearClient::run
+//! [`LinearClient::paginate`]: crate::client::LinearClient::paginate
+
+pub mod comment;
+pub mod cycle;
+pub mod document;
+pub mod issue;
+pub mod label;
+pub mod project;
+pub mod resolve;
+pub mod search;
+pub mod sync;
+pub mod team;
+pub mod user;
+pub mod workflow;
+
+use crate::generated::schema;
+
+#[derive(cynic::QueryFragment)]
+pub struct PageInfo {
+    pub has_next_page: bool,
+    pub end_cursor: Option<String>,
+}
+
+/// Variables for connection queries that take nothing but a page.
+#[derive(cynic::QueryVariables)]
+pub struct PageVariables {
+    pub first: i32,
+    pub after: Option<String>,
+}
+
+/// Variables for queries that fetch a single object by ID.
+#[derive(cynic::QueryVariables)]
+pub struct IdVariables {
+    pub id: String,
+}
+
+/// Variables for connection queries nested under an object fetched by ID.
+#[derive(cynic::QueryVariables)]
+pub struct IdPageVariables {
+    pub id: String,
+    pub first: i32,
+    pub after: Option<String>,
+}
+
+/// Declare the connection fragment selecting `nodes` and `pageInfo` of a
+/// Relay connection, convertible into a [`Connection`] for pagination.
+///
+/// [`Connection`]: crate::client::Connection
+macro_rules! connection {
+    ($name:ident, $graphql_type:literal, $node:ty) => {
+        #[derive(cynic::QueryFragment)]
+        #[cynic(graphql_type = $graphql_type)]
+        pub struct $name {
+            pub nodes: Vec<$node>,
+            pub page_info: $crate::queries::PageInfo,
+        }
+
+        impl From<$name> for $crate::client::Connection<$node> {
+            fn from(connection: $name) -> Self {
+                Self {
+                    nodes: connection.nodes,
+                    page_info: connection.page_info,
+                }
+            }
+        }
+    };
+}
+pub(crate) use connection;
+
+#[derive(cynic::InputObject, Default)]
+pub struct StringComparator {
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub eq: Option<String>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub contains: Option<String>,
+}
+
+impl StringComparator {
+    pub fn eq(value: &str) -> Self {
+        Self {
+            eq: Some(value.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn contains(value: &str) -> Self {
+        Self {
+            contains: Some(value.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(cynic::InputObject)]
+#[cynic(graphql_type = "IDComparator")]
+pub struct IdComparator {
+    pub eq: cynic::Id,
+}
+
+impl IdComparator {
+    pub fn eq(id: &str) -> Self {
+        Self {
+            eq: cynic::Id::new(id),
+        }
+    }
+}
+
+#[derive(cynic::InputObject)]
+pub struct DateComparator {
+    pub gte: String,
+}
+
+#[derive(cynic::InputObject)]
+pub struct NumberComparator {
+    pub eq: f64,
+}
+
+#[derive(cynic::InputObject)]
+pub struct BooleanComparator {
+    pub eq: bool,
+}
+
+#[derive(cynic::InputObject, Default)]
+pub struct TeamFilter {
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub id: Option<IdComparator>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub key: Option<StringComparator>,
+}
+
+impl TeamFilter {
+    pub fn key(key: &str) -> Self {
+        Self {
+            key: Some(StringComparator::eq(key)),
+            ..Default::default()
+        }
+    }
+
+    pub fn id(id: &str) -> Self {
+        Self {
+            id: Some(IdComparator::eq(id)),
+            ..Default::default()
+        }
+    }
+}
+
+/// A nullable input field an update leaves alone, sets, or clears by sending
+/// an explicit `null`. Mark such fields with
+/// `skip_serializing_if = "Patch::is_keep"`.
+#[derive(Default)]
+pub enum Patch<T> {
+    #[default]
+    Keep,
+    Set(T),
+    Clear,
+}
+
+impl<T> Patch<T> {
+    pub fn is_keep(&self) -> bool {
+        matches!(self, Patch::Keep)
+    }
+}
+
+impl<T: serde::Serialize> serde::Serialize for Patch<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Patch::Set(value) => serializer.serialize_some(value),
+            Patch::Keep | Patch::Clear => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<T, U: cynic::schema::IsScalar<T>> cynic::schema::IsScalar<T> for Patch<U> {
+    type SchemaType = Option<U::SchemaType>;
+}
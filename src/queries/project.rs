@@ -0,0 +1,101 @@
+use serde::Serialize;
+
+use super::{connection, DateComparator, IdComparator, IdVariablesFields, TeamFilter};
+use crate::generated::schema;
+
+#[derive(cynic::QueryVariables)]
+pub struct ProjectsVariables {
+    pub first: i32,
+    pub after: Option<String>,
+    pub filter: ProjectFilter,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "ProjectsVariables")]
+pub struct ProjectsQuery {
+    #[arguments(first: $first, after: $after, filter: $filter)]
+    pub projects: ProjectConnection,
+}
+
+connection!(ProjectConnection, "ProjectConnection", Project);
+
+#[derive(cynic::QueryFragment, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Project {
+    pub id: cynic::Id,
+    pub name: String,
+    pub state: String,
+    pub progress: f64,
+    pub start_date: Option<String>,
+    pub target_date: Option<String>,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "IdVariables")]
+pub struct ProjectQuery {
+    #[arguments(id: $id)]
+    pub project: ProjectDetail,
+}
+
+#[derive(cynic::QueryFragment, Serialize)]
+#[cynic(graphql_type = "Project")]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectDetail {
+    pub id: cynic::Id,
+    pub name: String,
+    pub description: Option<String>,
+    pub state: String,
+    pub progress: f64,
+    pub start_date: Option<String>,
+    pub target_date: Option<String>,
+}
+
+#[derive(cynic::InputObject, Default)]
+pub struct ProjectFilter {
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub id: Option<IdComparator>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub accessible_teams: Option<TeamCollectionFilter>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateComparator>,
+}
+
+#[derive(cynic::InputObject)]
+pub struct TeamCollectionFilter {
+    pub some: TeamFilter,
+}
+
+#[derive(cynic::QueryVariables)]
+pub struct CreateProjectVariables {
+    pub input: ProjectCreateInput,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Mutation", variables = "CreateProjectVariables")]
+pub struct CreateProjectMutation {
+    #[arguments(input: $input)]
+    pub project_create: ProjectCreatePayload,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "ProjectPayload")]
+pub struct ProjectCreatePayload {
+    pub success: bool,
+    pub project: Option<CreatedProject>,
+}
+
+#[derive(cynic::QueryFragment, Serialize)]
+#[cynic(graphql_type = "Project")]
+pub struct CreatedProject {
+    pub id: cynic::Id,
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(cynic::InputObject)]
+pub struct ProjectCreateInput {
+    pub name: String,
+    pub team_ids: Vec<String>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
@@ -0,0 +1,153 @@
+//! Minimal selections used to resolve names into IDs.
+
+use serde::Serialize;
+
+use super::{
+    connection, cycle::CycleFilter, IdPageVariablesFields, IdVariablesFields, PageVariablesFields,
+};
+use crate::generated::schema;
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Team")]
+pub struct TeamRef {
+    pub id: cynic::Id,
+    pub key: String,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "PageVariables")]
+pub struct ResolveUsers {
+    #[arguments(first: $first, after: $after)]
+    pub users: UserRefConnection,
+}
+
+connection!(UserRefConnection, "UserConnection", UserRef);
+
+#[derive(cynic::QueryFragment, Serialize)]
+#[cynic(graphql_type = "User")]
+#[serde(rename_all = "camelCase")]
+pub struct UserRef {
+    pub id: cynic::Id,
+    pub name: String,
+    pub display_name: String,
+    pub email: String,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "PageVariables")]
+pub struct ResolveProjects {
+    #[arguments(first: $first, after: $after)]
+    pub projects: ProjectRefConnection,
+}
+
+connection!(ProjectRefConnection, "ProjectConnection", ProjectRef);
+
+#[derive(cynic::QueryFragment, Serialize)]
+#[cynic(graphql_type = "Project")]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectRef {
+    pub id: cynic::Id,
+    pub name: String,
+    pub slug_id: String,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "IdPageVariables")]
+pub struct ResolveMilestones {
+    #[arguments(id: $id)]
+    pub project: ProjectWithMilestones,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Project", variables = "IdPageVariables")]
+pub struct ProjectWithMilestones {
+    #[arguments(first: $first, after: $after)]
+    pub project_milestones: MilestoneRefConnection,
+}
+
+connection!(
+    MilestoneRefConnection,
+    "ProjectMilestoneConnection",
+    MilestoneRef
+);
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "ProjectMilestone")]
+pub struct MilestoneRef {
+    pub id: cynic::Id,
+    pub name: String,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query")]
+pub struct ResolveTemplates {
+    pub templates: Vec<TemplateRef>,
+}
+
+/// A template, which belongs to a team or, when `team` is unset, the whole
+/// workspace.
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Template")]
+pub struct TemplateRef {
+    pub id: cynic::Id,
+    pub name: String,
+    #[cynic(rename = "type")]
+    pub template_type: String,
+    pub team: Option<TeamRef>,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "IdVariables")]
+pub struct ResolveTeamSettings {
+    #[arguments(id: $id)]
+    pub team: TeamSettings,
+}
+
+/// The team settings issue fields are checked against before an issue is
+/// created or updated.
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Team")]
+pub struct TeamSettings {
+    pub id: cynic::Id,
+    pub key: String,
+    pub cycles_enabled: bool,
+    pub issue_estimation_type: String,
+    pub issue_estimation_allow_zero: bool,
+    pub issue_estimation_extended: bool,
+}
+
+#[derive(cynic::QueryVariables)]
+pub struct ResolveCycleVariables {
+    pub filter: CycleFilter,
+    pub first: i32,
+    pub after: Option<String>,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "ResolveCycleVariables")]
+pub struct ResolveCycle {
+    #[arguments(filter: $filter, first: $first, after: $after)]
+    pub cycles: CycleRefConnection,
+}
+
+connection!(CycleRefConnection, "CycleConnection", CycleRef);
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Cycle")]
+pub struct CycleRef {
+    pub id: cynic::Id,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "IdVariables")]
+pub struct ResolveIssue {
+    #[arguments(id: $id)]
+    pub issue: IssueRef,
+}
+
+/// An issue's ID, looked up by its identifier.
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Issue")]
+pub struct IssueRef {
+    pub id: cynic::Id,
+}
@@ -0,0 +1,35 @@
+use serde::Serialize;
+
+use super::connection;
+use crate::generated::schema;
+
+#[derive(cynic::QueryVariables)]
+pub struct SearchVariables {
+    pub term: String,
+    pub first: i32,
+    pub after: Option<String>,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "SearchVariables")]
+pub struct SearchIssuesQuery {
+    #[arguments(term: $term, first: $first, after: $after)]
+    pub search_issues: IssueSearchPayload,
+}
+
+connection!(IssueSearchPayload, "IssueSearchPayload", SearchResult);
+
+#[derive(cynic::QueryFragment, Serialize, Clone)]
+#[cynic(graphql_type = "IssueSearchResult")]
+pub struct SearchResult {
+    pub id: cynic::Id,
+    pub identifier: String,
+    pub title: String,
+    pub state: Option<SearchState>,
+}
+
+#[derive(cynic::QueryFragment, Serialize, Clone)]
+#[cynic(graphql_type = "WorkflowState")]
+pub struct SearchState {
+    pub name: String,
+}
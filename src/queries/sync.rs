@@ -0,0 +1,146 @@
+//! Full selections of the entities mirrored by `linear sync`, enough to
+//! answer the read commands offline.
+
+use super::comment::CommentUser;
+use super::cycle::CyclesVariablesFields;
+use super::issue::{IssueState, IssuesVariablesFields, Priority, Team};
+use super::project::ProjectsVariablesFields;
+use super::{connection, DateComparator, TeamFilter};
+use crate::generated::schema;
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "IssuesVariables")]
+pub struct SyncIssuesQuery {
+    #[arguments(first: $first, after: $after, filter: $filter)]
+    pub issues: SyncIssueConnection,
+}
+
+connection!(SyncIssueConnection, "IssueConnection", SyncIssue);
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Issue")]
+pub struct SyncIssue {
+    pub id: cynic::Id,
+    pub identifier: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: Priority,
+    pub created_at: String,
+    pub updated_at: String,
+    pub state: Option<IssueState>,
+    pub assignee: Option<SyncUser>,
+    pub team: Team,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "User")]
+pub struct SyncUser {
+    pub id: cynic::Id,
+    pub name: String,
+}
+
+#[derive(cynic::QueryVariables)]
+pub struct SyncCommentsVariables {
+    pub first: i32,
+    pub after: Option<String>,
+    pub filter: CommentFilter,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "SyncCommentsVariables")]
+pub struct SyncCommentsQuery {
+    #[arguments(first: $first, after: $after, filter: $filter)]
+    pub comments: SyncCommentConnection,
+}
+
+connection!(SyncCommentConnection, "CommentConnection", SyncComment);
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Comment")]
+pub struct SyncComment {
+    pub id: cynic::Id,
+    pub body: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub user: Option<CommentUser>,
+    pub issue: Option<SyncCommentIssue>,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Issue")]
+pub struct SyncCommentIssue {
+    pub id: cynic::Id,
+}
+
+#[derive(cynic::InputObject, Default)]
+pub struct CommentFilter {
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub issue: Option<NullableIssueFilter>,
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateComparator>,
+}
+
+#[derive(cynic::InputObject, Default)]
+pub struct NullableIssueFilter {
+    #[cynic(skip_serializing_if = "Option::is_none")]
+    pub team: Option<TeamFilter>,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "ProjectsVariables")]
+pub struct SyncProjectsQuery {
+    #[arguments(first: $first, after: $after, filter: $filter)]
+    pub projects: SyncProjectConnection,
+}
+
+connection!(SyncProjectConnection, "ProjectConnection", SyncProject);
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Project")]
+pub struct SyncProject {
+    pub id: cynic::Id,
+    pub name: String,
+    pub description: String,
+    pub state: String,
+    pub progress: f64,
+    pub start_date: Option<String>,
+    pub target_date: Option<String>,
+    pub updated_at: String,
+    #[arguments(first: 50)]
+    pub teams: SyncProjectTeams,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "TeamConnection")]
+pub struct SyncProjectTeams {
+    pub nodes: Vec<SyncTeamKey>,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Team")]
+pub struct SyncTeamKey {
+    pub key: String,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "CyclesVariables")]
+pub struct SyncCyclesQuery {
+    #[arguments(first: $first, after: $after, filter: $filter)]
+    pub cycles: SyncCycleConnection,
+}
+
+connection!(SyncCycleConnection, "CycleConnection", SyncCycle);
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Cycle")]
+pub struct SyncCycle {
+    pub id: cynic::Id,
+    pub number: f64,
+    pub name: Option<String>,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub progress: f64,
+    pub description: Option<String>,
+    pub updated_at: String,
+    pub team: SyncTeamKey,
+}
@@ -0,0 +1,28 @@
+use serde::Serialize;
+
+use super::{connection, IdVariablesFields, PageVariablesFields};
+use crate::generated::schema;
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "PageVariables")]
+pub struct TeamsQuery {
+    #[arguments(first: $first, after: $after)]
+    pub teams: TeamConnection,
+}
+
+connection!(TeamConnection, "TeamConnection", Team);
+
+#[derive(cynic::QueryFragment, Serialize, Clone)]
+pub struct Team {
+    pub id: cynic::Id,
+    pub key: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "IdVariables")]
+pub struct TeamQuery {
+    #[arguments(id: $id)]
+    pub team: Team,
+}
@@ -0,0 +1,38 @@
+use serde::Serialize;
+
+use crate::generated::schema;
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query")]
+pub struct ViewerQuery {
+    pub viewer: User,
+}
+
+/// Just the viewer's ID, for resolving `me` and for requests made only for
+/// their response headers.
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query")]
+pub struct ViewerIdQuery {
+    pub viewer: ViewerId,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "User")]
+pub struct ViewerId {
+    pub id: cynic::Id,
+}
+
+#[derive(cynic::QueryFragment, Serialize)]
+pub struct User {
+    pub id: cynic::Id,
+    pub name: String,
+    pub email: String,
+    pub organization: Organization,
+}
+
+#[derive(cynic::QueryFragment, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Organization {
+    pub name: String,
+    pub url_key: String,
+}
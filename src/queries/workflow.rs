@@ -0,0 +1,35 @@
+use serde::Serialize;
+
+use super::{connection, IdPageVariablesFields};
+use crate::generated::schema;
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "IdPageVariables")]
+pub struct WorkflowStatesQuery {
+    #[arguments(id: $id)]
+    pub team: TeamWithStates,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Team", variables = "IdPageVariables")]
+pub struct TeamWithStates {
+    #[arguments(first: $first, after: $after)]
+    pub states: WorkflowStateConnection,
+}
+
+connection!(
+    WorkflowStateConnection,
+    "WorkflowStateConnection",
+    WorkflowState
+);
+
+#[derive(cynic::QueryFragment, Serialize, Clone)]
+pub struct WorkflowState {
+    pub id: cynic::Id,
+    pub name: String,
+    #[cynic(rename = "type")]
+    #[serde(rename = "type")]
+    pub state_type: String,
+    pub color: String,
+    pub position: f64,
+}
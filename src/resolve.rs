@@ -0,0 +1,518 @@
+//! Resolution of the human-friendly names users type (team keys, state names,
+//! user names, ...) into the IDs Linear's mutations expect.
+//!
+//! Every resolver also accepts a raw Linear ID. Lookups prefer an exact
+//! (case-insensitive) match, fall back to a unique partial match, and fail
+//! with suggestions when nothing or more than one candidate matches.
+//!
+//! The candidates come from the metadata cache, so repeated lookups don't
+//! refetch every team, state, user, label or project. A name missing from a
+//! cached list refetches it once before failing, in case it was created
+//! since.
+
+use std::future::Future;
+
+use cynic::QueryBuilder;
+
+use crate::client::LinearClient;
+use crate::error::{Error, Result};
+use crate::queries::cycle::CycleFilter;
+use crate::queries::label::{Label, LabelsQuery};
+use crate::queries::resolve::{
+    ProjectRef, ResolveCycle, ResolveCycleVariables, ResolveIssue, ResolveMilestones,
+    ResolveProjects, ResolveTeamSettings, ResolveTemplates, ResolveUsers, TeamRef, UserRef,
+};
+use crate::queries::team::{Team, TeamsQuery};
+use crate::queries::user::ViewerIdQuery;
+use crate::queries::workflow::{WorkflowState, WorkflowStatesQuery};
+use crate::queries::{
+    BooleanComparator, IdPageVariables, IdVariables, NumberComparator, PageVariables, TeamFilter,
+};
+
+pub use crate::queries::resolve::{IssueRef, TeamSettings};
+
+/// T-shirt sizes and the points they stand for, smallest first.
+const T_SHIRT_SIZES: &[(&str, i32)] = &[
+    ("XS", 1),
+    ("S", 2),
+    ("M", 3),
+    ("L", 5),
+    ("XL", 8),
+    ("XXL", 13),
+    ("XXXL", 21),
+];
+
+/// Every team in the workspace.
+pub async fn teams(client: &LinearClient) -> Result<Vec<Team>> {
+    let fetch = client.paginate(
+        None,
+        |first, after| TeamsQuery::build(PageVariables { first, after }),
+        |r| r.teams.into(),
+    );
+    client.cached("teams", fetch).await
+}
+
+/// A team's workflow states.
+pub async fn states(client: &LinearClient, team_id: &str) -> Result<Vec<WorkflowState>> {
+    let fetch = client.paginate(
+        None,
+        |first, after| {
+            WorkflowStatesQuery::build(IdPageVariables {
+                id: team_id.to_string(),
+                first,
+                after,
+            })
+        },
+        |r| r.team.states.into(),
+    );
+    client.cached(&format!("states/{}", team_id), fetch).await
+}
+
+/// Every label in the workspace, including team labels.
+pub async fn all_labels(client: &LinearClient) -> Result<Vec<Label>> {
+    let fetch = client.paginate(
+        None,
+        |first, after| LabelsQuery::build(PageVariables { first, after }),
+        |r| r.issue_labels.into(),
+    );
+    client.cached("labels", fetch).await
+}
+
+/// Every user in the workspace.
+pub async fn users(client: &LinearClient) -> Result<Vec<UserRef>> {
+    let fetch = client.paginate(
+        None,
+        |first, after| ResolveUsers::build(PageVariables { first, after }),
+        |r| r.users.into(),
+    );
+    client.cached("users", fetch).await
+}
+
+/// Every project in the workspace.
+pub async fn projects(client: &LinearClient) -> Result<Vec<ProjectRef>> {
+    let fetch = client.paginate(
+        None,
+        |first, after| ResolveProjects::build(PageVariables { first, after }),
+        |r| r.projects.into(),
+    );
+    client.cached("projects", fetch).await
+}
+
+/// Run `find` on the list `load` returns. When that list was served from
+/// the cache entry `key` and `find` reports something missing, drop the
+/// entry and try once more on a freshly fetched list.
+async fn find_fresh<T, R, F>(
+    client: &LinearClient,
+    key: &str,
+    load: impl Fn() -> F,
+    find: impl Fn(&[T]) -> Result<R>,
+) -> Result<R>
+where
+    F: Future<Output = Result<Vec<T>>>,
+{
+    let cached = client.cache().is_fresh(key);
+    match find(&load().await?) {
+        Err(e) if cached && e.is_not_found() => {
+            client.cache().invalidate(key);
+            find(&load().await?)
+        }
+        result => result,
+    }
+}
+
+/// Resolve a team key, name or ID.
+pub async fn team(client: &LinearClient, input: &str) -> Result<Team> {
+    find_fresh(
+        client,
+        "teams",
+        || teams(client),
+        |teams| {
+            let index = pick("team", input, teams, Some("linear team list"), |t| {
+                vec![t.id.inner(), t.key.as_str(), t.name.as_str()]
+            })?;
+            Ok(teams[index].clone())
+        },
+    )
+    .await
+}
+
+/// Resolve an issue identifier (e.g. ENG-123) or ID.
+pub async fn issue(client: &LinearClient, input: &str) -> Result<IssueRef> {
+    let response = client
+        .run(ResolveIssue::build(IdVariables {
+            id: input.to_string(),
+        }))
+        .await?;
+    Ok(response.issue)
+}
+
+/// Resolve a workflow state name within a team.
+pub async fn state(client: &LinearClient, team: &TeamRef, input: &str) -> Result<String> {
+    let team_id = team.id.inner();
+    let list = format!("linear workflow list --team {}", team.key);
+    find_fresh(
+        client,
+        &format!("states/{}", team_id),
+        || states(client, team_id),
+        |states| {
+            let index = pick("state", input, states, Some(&list), |s| {
+                vec![s.id.inner(), s.name.as_str()]
+            })?;
+            Ok(states[index].id.inner().to_string())
+        },
+    )
+    .await
+}
+
+/// Resolve a user by name, display name, email, ID, or `me`.
+pub async fn user(client: &LinearClient, input: &str) -> Result<String> {
+    if input.eq_ignore_ascii_case("me") {
+        let response = client.run(ViewerIdQuery::build(())).await?;
+        return Ok(response.viewer.id.into_inner());
+    }
+
+    find_fresh(
+        client,
+        "users",
+        || users(client),
+        |users| {
+            let index = pick("user", input, users, None, |u| {
+                vec![
+                    u.id.inner(),
+                    u.email.as_str(),
+                    u.display_name.as_str(),
+                    u.name.as_str(),
+                ]
+            })?;
+            Ok(users[index].id.inner().to_string())
+        },
+    )
+    .await
+}
+
+/// The labels available to a team: its own labels plus workspace labels.
+pub async fn team_labels(client: &LinearClient, team_id: &str) -> Result<Vec<Label>> {
+    Ok(all_labels(client)
+        .await?
+        .into_iter()
+        .filter(|l| l.team.as_ref().is_none_or(|t| t.id.inner() == team_id))
+        .collect())
+}
+
+/// Resolve label names available to a team.
+pub async fn labels(
+    client: &LinearClient,
+    team_id: &str,
+    inputs: &[String],
+) -> Result<Vec<String>> {
+    find_fresh(
+        client,
+        "labels",
+        || team_labels(client, team_id),
+        |labels| {
+            inputs
+                .iter()
+                .map(|input| {
+                    let index = pick("label", input, labels, Some("linear label list"), |l| {
+                        vec![l.id.inner(), l.name.as_str()]
+                    })?;
+                    Ok(labels[index].id.inner().to_string())
+                })
+                .collect()
+        },
+    )
+    .await
+}
+
+/// Resolve a project name, slug ID or ID.
+pub async fn project(client: &LinearClient, input: &str) -> Result<String> {
+    find_fresh(
+        client,
+        "projects",
+        || projects(client),
+        |projects| {
+            let index = pick(
+                "project",
+                input,
+                projects,
+                Some("linear project list"),
+                |p| vec![p.id.inner(), p.slug_id.as_str(), p.name.as_str()],
+            )?;
+            Ok(projects[index].id.inner().to_string())
+        },
+    )
+    .await
+}
+
+/// Resolve a milestone name or ID within a project.
+pub async fn milestone(client: &LinearClient, project_id: &str, input: &str) -> Result<String> {
+    let milestones = client
+        .paginate(
+            None,
+            |first, after| {
+                ResolveMilestones::build(IdPageVariables {
+                    id: project_id.to_string(),
+                    first,
+                    after,
+                })
+            },
+            |r| r.project.project_milestones.into(),
+        )
+        .await?;
+
+    let index = pick("milestone", input, &milestones, None, |m| {
+        vec![m.id.inner(), m.name.as_str()]
+    })?;
+    Ok(milestones[index].id.inner().to_string())
+}
+
+/// Resolve an issue template name or ID available to a team: its own
+/// templates plus workspace templates.
+pub async fn template(client: &LinearClient, team_id: &str, input: &str) -> Result<String> {
+    let templates: Vec<_> = client
+        .run(ResolveTemplates::build(()))
+        .await?
+        .templates
+        .into_iter()
+        .filter(|t| t.template_type == "issue")
+        .filter(|t| {
+            t.team
+                .as_ref()
+                .is_none_or(|team| team.id.inner() == team_id)
+        })
+        .collect();
+
+    let index = pick("template", input, &templates, None, |t| {
+        vec![t.id.inner(), t.name.as_str()]
+    })?;
+    Ok(templates[index].id.inner().to_string())
+}
+
+/// The settings of a team that issue fields are validated against.
+pub async fn team_settings(client: &LinearClient, team_id: &str) -> Result<TeamSettings> {
+    let response = client
+        .run(ResolveTeamSettings::build(IdVariables {
+            id: team_id.to_string(),
+        }))
+        .await?;
+    Ok(response.team)
+}
+
+/// Resolve a cycle number, `current` or `next` within a team.
+pub async fn cycle(client: &LinearClient, team: &TeamSettings, input: &str) -> Result<String> {
+    if !team.cycles_enabled {
+        return Err(Error::Validation(format!(
+            "team {} doesn't use cycles",
+            team.key
+        )));
+    }
+
+    let which = input.to_lowercase();
+    let number = match which.as_str() {
+        "current" | "next" => None,
+        number => Some(number.parse::<u32>().map_err(|_| {
+            Error::InvalidInput(format!(
+                "invalid cycle '{}', expected a number, `current` or `next`",
+                input
+            ))
+        })?),
+    };
+    let flag = |name: &str| (which == name).then_some(BooleanComparator { eq: true });
+
+    let cycles = client
+        .paginate(
+            Some(1),
+            |first, after| {
+                ResolveCycle::build(ResolveCycleVariables {
+                    filter: CycleFilter {
+                        team: Some(TeamFilter::id(team.id.inner())),
+                        number: number.map(|n| NumberComparator { eq: f64::from(n) }),
+                        is_active: flag("current"),
+                        is_next: flag("next"),
+                        ..Default::default()
+                    },
+                    first,
+                    after,
+                })
+            },
+            |r| r.cycles.into(),
+        )
+        .await?;
+
+    let list = format!("linear cycle list --team {}", team.key);
+    cycles
+        .into_iter()
+        .next()
+        .map(|c| c.id.into_inner())
+        .ok_or_else(|| {
+            Error::NotFound(format!("{} cycle in team {}", input, team.key))
+                .with_hint(format!("run `{}` to see its cycles", list))
+        })
+}
+
+/// Check an estimate against the team's scale, returning its points. Teams
+/// using T-shirt sizes may give a size (e.g. `M`) instead of points.
+pub fn estimate(team: &TeamSettings, input: &str) -> Result<i32> {
+    let base: &[i32] = match team.issue_estimation_type.as_str() {
+        "notUsed" => {
+            return Err(Error::Validation(format!(
+                "team {} doesn't use estimates",
+                team.key
+            )))
+        }
+        "exponential" => &[1, 2, 4, 8, 16, 32, 64],
+        "linear" => &[1, 2, 3, 4, 5, 6, 7],
+        // Fibonacci, and T-shirt sizes, which map onto the same points
+        _ => &[1, 2, 3, 5, 8, 13, 21],
+    };
+    let extended = if team.issue_estimation_extended { 7 } else { 5 };
+    let zero = team.issue_estimation_allow_zero.then_some(0);
+    let scale: Vec<i32> = zero
+        .into_iter()
+        .chain(base[..extended].iter().copied())
+        .collect();
+
+    let t_shirt = team.issue_estimation_type == "tShirt";
+    let size = T_SHIRT_SIZES
+        .iter()
+        .find(|(name, _)| t_shirt && name.eq_ignore_ascii_case(input));
+    let points = match size {
+        Some((_, points)) => Some(*points),
+        None => input.parse().ok(),
+    };
+    if let Some(points) = points.filter(|p| scale.contains(p)) {
+        return Ok(points);
+    }
+
+    let valid: Vec<String> = scale
+        .iter()
+        .map(
+            |&points| match T_SHIRT_SIZES.iter().find(|(_, p)| t_shirt && *p == points) {
+                Some((name, _)) => format!("{} ({})", name, points),
+                None => points.to_string(),
+            },
+        )
+        .collect();
+    Err(Error::Validation(format!(
+        "estimate '{}' isn't on team {}'s {} scale",
+        input, team.key, team.issue_estimation_type
+    ))
+    .with_hint(format!("use one of {}", valid.join(", "))))
+}
+
+/// Find the single candidate `input` refers to, returning its index.
+///
+/// `names` lists every string a candidate may be referred to by, its ID
+/// first. Exact case-insensitive matches win; failing that a unique
+/// substring match of any name but the ID is accepted, since short inputs
+/// would otherwise match random stretches of IDs. When nothing matches, the
+/// error hints at the closest names, or at the `list` command that shows
+/// every candidate.
+fn pick<T>(
+    kind: &str,
+    input: &str,
+    candidates: &[T],
+    list: Option<&str>,
+    names: impl Fn(&T) -> Vec<&str>,
+) -> Result<usize> {
+    let needle = input.to_lowercase();
+
+    let exact: Vec<usize> = (0..candidates.len())
+        .filter(|&i| {
+            names(&candidates[i])
+                .iter()
+                .any(|n| n.to_lowercase() == needle)
+        })
+        .collect();
+    let matches = if exact.is_empty() {
+        (0..candidates.len())
+            .filter(|&i| {
+                names(&candidates[i])
+                    .iter()
+                    .skip(1)
+                    .any(|n| n.to_lowercase().contains(&needle))
+            })
+            .collect()
+    } else {
+        exact
+    };
+
+    match matches.as_slice() {
+        [index] => Ok(*index),
+        [] => {
+            let error = Error::NotFound(format!("{} '{}'", kind, input));
+            let suggestions = suggest(input, candidates.iter().map(&names));
+            Err(match (suggestions.is_empty(), list) {
+                (false, _) => error.with_hint(format!(
+                    "did you mean {} {}?",
+                    kind,
+                    suggestions.join(" or ")
+                )),
+                (true, Some(list)) => {
+                    error.with_hint(format!("run `{}` to see valid {}s", list, kind))
+                }
+                (true, None) => error,
+            })
+        }
+        many => {
+            let options: Vec<String> = many
+                .iter()
+                .map(|&i| display(&names(&candidates[i])))
+                .collect();
+            Err(Error::Ambiguous(format!(
+                "{} '{}' matches {}",
+                kind,
+                input,
+                options.join(", ")
+            )))
+        }
+    }
+}
+
+/// The name a candidate is listed by in ambiguity errors: the last, most
+/// readable of its names.
+fn display(names: &[&str]) -> String {
+    let name = names.last().copied().unwrap_or_default();
+    format!("'{}'", name)
+}
+
+/// Up to three candidates within a small edit distance of `input`, each
+/// shown by whichever of its names (other than the ID) is closest.
+fn suggest<'a>(input: &str, candidates: impl Iterator<Item = Vec<&'a str>>) -> Vec<String> {
+    let needle = input.to_lowercase();
+    let max_distance = (needle.chars().count() / 3).max(2);
+
+    let mut scored: Vec<(usize, &str)> = candidates
+        .filter_map(|names| {
+            names
+                .into_iter()
+                .skip(1)
+                .map(|n| (levenshtein(&needle, &n.to_lowercase()), n))
+                .min()
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    scored.sort();
+    scored
+        .into_iter()
+        .take(3)
+        .map(|(_, name)| format!("`{}`", name))
+        .collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous + usize::from(ca != *cb);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+        }
+    }
+
+    row[b.len()]
+}
@@ -0,0 +1,597 @@
+//! Local SQLite mirror of a workspace's issues, comments, projects and
+//! cycles, filled by `linear sync` and read by commands run with `--offline`.
+//!
+//! Each profile has its own database under `$XDG_DATA_HOME/linear` (falling
+//! back to `~/.local/share/linear`). Sync progress is tracked per entity type
+//! and team in `sync_state`: the high-water mark is the latest `updatedAt`
+//! fully synced, and an interrupted sync leaves its cursor behind so the next
+//! run picks up where it stopped.
+
+use std::fs;
+use std::path::PathBuf;
+
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::queries::comment::{Comment, CommentUser};
+use crate::queries::cycle::{Cycle, CycleDetail};
+use crate::queries::issue::{Assignee, Issue, IssueDetail, IssueState, Priority, Team};
+use crate::queries::project::{Project, ProjectDetail};
+use crate::queries::search::{SearchResult, SearchState};
+use crate::queries::sync::{SyncComment, SyncCycle, SyncIssue, SyncProject};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS issues (
+    id TEXT PRIMARY KEY,
+    identifier TEXT NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT,
+    priority REAL NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    state_name TEXT,
+    assignee_id TEXT,
+    assignee_name TEXT,
+    team_key TEXT NOT NULL,
+    team_name TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS issues_identifier ON issues (identifier COLLATE NOCASE);
+CREATE INDEX IF NOT EXISTS issues_team ON issues (team_key COLLATE NOCASE);
+
+CREATE TABLE IF NOT EXISTS comments (
+    id TEXT PRIMARY KEY,
+    issue_id TEXT,
+    body TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    user_name TEXT
+);
+CREATE INDEX IF NOT EXISTS comments_issue ON comments (issue_id);
+
+CREATE TABLE IF NOT EXISTS projects (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL,
+    state TEXT NOT NULL,
+    progress REAL NOT NULL,
+    start_date TEXT,
+    target_date TEXT,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS project_teams (
+    project_id TEXT NOT NULL,
+    team_key TEXT NOT NULL,
+    PRIMARY KEY (project_id, team_key)
+);
+
+CREATE TABLE IF NOT EXISTS cycles (
+    id TEXT PRIMARY KEY,
+    number REAL NOT NULL,
+    name TEXT,
+    starts_at TEXT NOT NULL,
+    ends_at TEXT NOT NULL,
+    progress REAL NOT NULL,
+    description TEXT,
+    updated_at TEXT NOT NULL,
+    team_key TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS sync_state (
+    entity TEXT NOT NULL,
+    scope TEXT NOT NULL,
+    high_water TEXT,
+    since TEXT,
+    cursor TEXT,
+    max_seen TEXT,
+    synced_at INTEGER,
+    PRIMARY KEY (entity, scope)
+);
+
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+";
+
+/// Progress of syncing one entity type for one scope (a team key, or `*`
+/// for the whole workspace).
+#[derive(Default)]
+pub struct SyncState {
+    /// Latest `updatedAt` of a completed sync
+    pub high_water: Option<String>,
+    /// The `updatedAt` lower bound of the sync in progress
+    pub since: Option<String>,
+    /// Cursor of the next page of the sync in progress
+    pub cursor: Option<String>,
+    /// Latest `updatedAt` seen by the sync in progress
+    pub max_seen: Option<String>,
+}
+
+/// What one `linear sync` run did for an entity type.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSummary {
+    pub entity: &'static str,
+    /// Records created or updated by this run
+    pub synced: usize,
+    /// Latest `updatedAt` now mirrored
+    pub high_water: Option<String>,
+}
+
+/// An entity stored by `linear sync`.
+pub trait Record {
+    /// The `sync_state` entity name
+    const ENTITY: &'static str;
+    fn updated_at(&self) -> &str;
+    fn save(&self, tx: &Transaction) -> rusqlite::Result<()>;
+}
+
+/// Where the given profile's mirror is kept.
+pub fn path(profile: &str) -> Result<PathBuf> {
+    let dir = match std::env::var_os("XDG_DATA_HOME").filter(|d| !d.is_empty()) {
+        Some(dir) => PathBuf::from(dir).join("linear"),
+        None => dirs::home_dir()
+            .map(|home| home.join(".local").join("share").join("linear"))
+            .ok_or_else(|| Error::Config("could not determine home directory".to_string()))?,
+    };
+    Ok(dir.join(format!("{}.db", profile)))
+}
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Open the profile's mirror, creating it if needed.
+    pub fn open(profile: &str) -> Result<Self> {
+        let path = path(profile)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| Error::io(format!("failed to create {}", parent.display()), e))?;
+        }
+        let conn = Connection::open(&path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Open the profile's mirror for reading, failing if it was never synced.
+    pub fn open_existing(profile: &str) -> Result<Self> {
+        if !path(profile)?.exists() {
+            return Err(Error::Config(format!(
+                "no local data for profile '{}'; run `linear sync` first",
+                profile
+            )));
+        }
+        Self::open(profile)
+    }
+
+    pub fn sync_state(&self, entity: &str, scope: &str) -> Result<SyncState> {
+        let state = self
+            .conn
+            .query_row(
+                "SELECT high_water, since, cursor, max_seen FROM sync_state
+                 WHERE entity = ?1 AND scope = ?2",
+                params![entity, scope],
+                |row| {
+                    Ok(SyncState {
+                        high_water: row.get(0)?,
+                        since: row.get(1)?,
+                        cursor: row.get(2)?,
+                        max_seen: row.get(3)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(state.unwrap_or_default())
+    }
+
+    /// Store one page of records together with the sync progress it
+    /// represents, so an interrupted sync never loses or skips a page.
+    pub fn save_page<T: Record>(
+        &mut self,
+        scope: &str,
+        records: &[T],
+        state: &SyncState,
+    ) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        for record in records {
+            record.save(&tx)?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO sync_state
+                 (entity, scope, high_water, since, cursor, max_seen, synced_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                T::ENTITY,
+                scope,
+                state.high_water,
+                state.since,
+                state.cursor,
+                state.max_seen,
+                crate::time::now() as i64
+            ],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn set_meta(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    fn meta(&self, key: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| {
+                row.get(0)
+            })
+            .optional()?)
+    }
+
+    /// Issues matching the `issue list` filters, newest first.
+    pub fn issues(
+        &self,
+        team: Option<&str>,
+        state: Option<&str>,
+        assignee: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Issue>> {
+        let viewer = match assignee {
+            Some(a) if a.eq_ignore_ascii_case("me") => {
+                Some(self.meta("viewer_id")?.ok_or_else(|| {
+                    Error::Config("run `linear sync` to filter by \"me\" offline".to_string())
+                })?)
+            }
+            _ => None,
+        };
+        let assignee_name = assignee.filter(|_| viewer.is_none());
+
+        let mut statement = self.conn.prepare(
+            "SELECT identifier, title, state_name, assignee_name, priority FROM issues
+             WHERE (?1 IS NULL OR team_key = ?1 COLLATE NOCASE)
+               AND (?2 IS NULL OR state_name = ?2 COLLATE NOCASE)
+               AND (?3 IS NULL OR assignee_id = ?3)
+               AND (?4 IS NULL OR assignee_name LIKE '%' || ?4 || '%')
+             ORDER BY created_at DESC
+             LIMIT ?5",
+        )?;
+        let rows = statement.query_map(
+            params![team, state, viewer, assignee_name, limit_param(limit)],
+            |row| {
+                Ok(Issue {
+                    identifier: row.get(0)?,
+                    title: row.get(1)?,
+                    state: row
+                        .get::<_, Option<String>>(2)?
+                        .map(|name| IssueState { name }),
+                    assignee: row
+                        .get::<_, Option<String>>(3)?
+                        .map(|name| Assignee { name }),
+                    priority: Priority::from(row.get::<_, f64>(4)?),
+                })
+            },
+        )?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// An issue by identifier (e.g. ENG-123) or ID.
+    pub fn issue(&self, id: &str) -> Result<IssueDetail> {
+        self.conn
+            .query_row(
+                "SELECT identifier, title, description, state_name, assignee_name, priority,
+                        created_at, updated_at, team_key, team_name
+                 FROM issues WHERE id = ?1 OR identifier = ?1 COLLATE NOCASE",
+                [id],
+                |row| {
+                    Ok(IssueDetail {
+                        identifier: row.get(0)?,
+                        title: row.get(1)?,
+                        description: row.get(2)?,
+                        state: row
+                            .get::<_, Option<String>>(3)?
+                            .map(|name| IssueState { name }),
+                        assignee: row
+                            .get::<_, Option<String>>(4)?
+                            .map(|name| Assignee { name }),
+                        priority: Priority::from(row.get::<_, f64>(5)?),
+                        created_at: row.get(6)?,
+                        updated_at: row.get(7)?,
+                        team: Team {
+                            key: row.get(8)?,
+                            name: row.get(9)?,
+                        },
+                    })
+                },
+            )
+            .optional()?
+            .ok_or_else(|| not_synced("issue", id))
+    }
+
+    /// Identifiers and titles of the most recently updated issues.
+    pub fn recent_issues(&self, limit: u32) -> Result<Vec<(String, String)>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT identifier, title FROM issues ORDER BY updated_at DESC LIMIT ?1")?;
+        let rows = statement.query_map([limit], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Issues whose identifier, title or description contains `term`.
+    pub fn search(&self, term: &str, limit: Option<u32>) -> Result<Vec<SearchResult>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, identifier, title, state_name FROM issues
+             WHERE identifier LIKE '%' || ?1 || '%'
+                OR title LIKE '%' || ?1 || '%'
+                OR description LIKE '%' || ?1 || '%'
+             ORDER BY updated_at DESC
+             LIMIT ?2",
+        )?;
+        let rows = statement.query_map(params![term, limit_param(limit)], |row| {
+            Ok(SearchResult {
+                id: cynic::Id::new(row.get::<_, String>(0)?),
+                identifier: row.get(1)?,
+                title: row.get(2)?,
+                state: row
+                    .get::<_, Option<String>>(3)?
+                    .map(|name| SearchState { name }),
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Comments on an issue, oldest first.
+    pub fn comments(&self, issue: &str) -> Result<Vec<Comment>> {
+        let issue_id: String = self
+            .conn
+            .query_row(
+                "SELECT id FROM issues WHERE id = ?1 OR identifier = ?1 COLLATE NOCASE",
+                [issue],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| not_synced("issue", issue))?;
+
+        let mut statement = self.conn.prepare(
+            "SELECT id, body, created_at, user_name FROM comments
+             WHERE issue_id = ?1 ORDER BY created_at",
+        )?;
+        let rows = statement.query_map([issue_id], |row| {
+            Ok(Comment {
+                id: cynic::Id::new(row.get::<_, String>(0)?),
+                body: row.get(1)?,
+                created_at: row.get(2)?,
+                user: row
+                    .get::<_, Option<String>>(3)?
+                    .map(|name| CommentUser { name }),
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Projects, optionally only those accessible to a team.
+    pub fn projects(&self, team: Option<&str>, limit: Option<u32>) -> Result<Vec<Project>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, name, state, progress, start_date, target_date FROM projects
+             WHERE ?1 IS NULL OR EXISTS (
+                 SELECT 1 FROM project_teams
+                 WHERE project_id = projects.id AND team_key = ?1 COLLATE NOCASE)
+             ORDER BY updated_at DESC
+             LIMIT ?2",
+        )?;
+        let rows = statement.query_map(params![team, limit_param(limit)], |row| {
+            Ok(Project {
+                id: cynic::Id::new(row.get::<_, String>(0)?),
+                name: row.get(1)?,
+                state: row.get(2)?,
+                progress: row.get(3)?,
+                start_date: row.get(4)?,
+                target_date: row.get(5)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn project(&self, id: &str) -> Result<ProjectDetail> {
+        self.conn
+            .query_row(
+                "SELECT id, name, description, state, progress, start_date, target_date
+                 FROM projects WHERE id = ?1 OR name = ?1 COLLATE NOCASE",
+                [id],
+                |row| {
+                    Ok(ProjectDetail {
+                        id: cynic::Id::new(row.get::<_, String>(0)?),
+                        name: row.get(1)?,
+                        description: row.get(2)?,
+                        state: row.get(3)?,
+                        progress: row.get(4)?,
+                        start_date: row.get(5)?,
+                        target_date: row.get(6)?,
+                    })
+                },
+            )
+            .optional()?
+            .ok_or_else(|| not_synced("project", id))
+    }
+
+    /// Cycles, optionally of one team, most recent first.
+    pub fn cycles(&self, team: Option<&str>, limit: Option<u32>) -> Result<Vec<Cycle>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, number, name, starts_at, ends_at, progress FROM cycles
+             WHERE ?1 IS NULL OR team_key = ?1 COLLATE NOCASE
+             ORDER BY starts_at DESC
+             LIMIT ?2",
+        )?;
+        let rows = statement.query_map(params![team, limit_param(limit)], |row| {
+            Ok(Cycle {
+                id: cynic::Id::new(row.get::<_, String>(0)?),
+                number: row.get(1)?,
+                name: row.get(2)?,
+                starts_at: row.get(3)?,
+                ends_at: row.get(4)?,
+                progress: row.get(5)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn cycle(&self, id: &str) -> Result<CycleDetail> {
+        self.conn
+            .query_row(
+                "SELECT id, number, name, starts_at, ends_at, progress, description
+                 FROM cycles WHERE id = ?1",
+                [id],
+                |row| {
+                    Ok(CycleDetail {
+                        id: cynic::Id::new(row.get::<_, String>(0)?),
+                        number: row.get(1)?,
+                        name: row.get(2)?,
+                        starts_at: row.get(3)?,
+                        ends_at: row.get(4)?,
+                        progress: row.get(5)?,
+                        description: row.get(6)?,
+                    })
+                },
+            )
+            .optional()?
+            .ok_or_else(|| not_synced("cycle", id))
+    }
+}
+
+/// SQLite treats a negative LIMIT as no limit.
+fn limit_param(limit: Option<u32>) -> i64 {
+    limit.map_or(-1, i64::from)
+}
+
+fn not_synced(kind: &str, id: &str) -> Error {
+    Error::NotFound(format!(
+        "{} '{}' in the local store (run `linear sync`)",
+        kind, id
+    ))
+}
+
+impl Record for SyncIssue {
+    const ENTITY: &'static str = "issues";
+
+    fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+
+    fn save(&self, tx: &Transaction) -> rusqlite::Result<()> {
+        tx.execute(
+            "INSERT OR REPLACE INTO issues
+                 (id, identifier, title, description, priority, created_at, updated_at,
+                  state_name, assignee_id, assignee_name, team_key, team_name)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                self.id.inner(),
+                self.identifier,
+                self.title,
+                self.description,
+                self.priority.0,
+                self.created_at,
+                self.updated_at,
+                self.state.as_ref().map(|s| &s.name),
+                self.assignee.as_ref().map(|a| a.id.inner()),
+                self.assignee.as_ref().map(|a| &a.name),
+                self.team.key,
+                self.team.name,
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+impl Record for SyncComment {
+    const ENTITY: &'static str = "comments";
+
+    fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+
+    fn save(&self, tx: &Transaction) -> rusqlite::Result<()> {
+        tx.execute(
+            "INSERT OR REPLACE INTO comments (id, issue_id, body, created_at, updated_at, user_name)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                self.id.inner(),
+                self.issue.as_ref().map(|i| i.id.inner()),
+                self.body,
+                self.created_at,
+                self.updated_at,
+                self.user.as_ref().map(|u| &u.name),
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+impl Record for SyncProject {
+    const ENTITY: &'static str = "projects";
+
+    fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+
+    fn save(&self, tx: &Transaction) -> rusqlite::Result<()> {
+        tx.execute(
+            "INSERT OR REPLACE INTO projects
+                 (id, name, description, state, progress, start_date, target_date, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                self.id.inner(),
+                self.name,
+                self.description,
+                self.state,
+                self.progress,
+                self.start_date,
+                self.target_date,
+                self.updated_at,
+            ],
+        )?;
+        tx.execute(
+            "DELETE FROM project_teams WHERE project_id = ?1",
+            [self.id.inner()],
+        )?;
+        for team in &self.teams.nodes {
+            tx.execute(
+                "INSERT OR IGNORE INTO project_teams (project_id, team_key) VALUES (?1, ?2)",
+                params![self.id.inner(), team.key],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Record for SyncCycle {
+    const ENTITY: &'static str = "cycles";
+
+    fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+
+    fn save(&self, tx: &Transaction) -> rusqlite::Result<()> {
+        tx.execute(
+            "INSERT OR REPLACE INTO cycles
+                 (id, number, name, starts_at, ends_at, progress, description, updated_at, team_key)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                self.id.inner(),
+                self.number,
+                self.name,
+                self.starts_at,
+                self.ends_at,
+                self.progress,
+                self.description,
+                self.updated_at,
+                self.team.key,
+            ],
+        )?;
+        Ok(())
+    }
+}
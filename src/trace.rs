@@ -0,0 +1,38 @@
+//! Logging of API traffic to stderr for `--verbose` and `--debug`.
+//!
+//! The client emits `tracing` events inside a `graphql` span per operation:
+//! status, latency and response size at info level, and variables and
+//! rate-limit headers at debug level. Credentials are never logged.
+
+use std::io::IsTerminal;
+
+use clap::ValueEnum;
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum Format {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per event, for log tooling
+    Json,
+}
+
+/// Start logging this crate's events at `level` and above to stderr.
+pub fn init(level: LevelFilter, format: Format, color: bool) {
+    if level == LevelFilter::OFF {
+        return;
+    }
+    let targets = Targets::new().with_target(env!("CARGO_CRATE_NAME"), level);
+    let layer = tracing_subscriber::fmt::layer().with_writer(std::io::stderr);
+    let registry = tracing_subscriber::registry().with(targets);
+    match format {
+        Format::Text => registry
+            .with(layer.with_ansi(color && std::io::stderr().is_terminal()))
+            .init(),
+        Format::Json => registry.with(layer.json().with_current_span(true)).init(),
+    }
+}
@@ -0,0 +1,386 @@
+//! How GraphQL requests reach an endpoint.
+//!
+//! `LinearClient` builds the request body and interprets the response, while
+//! a [`Transport`] moves the bytes: [`HttpTransport`] posts to the API,
+//! [`ReplayTransport`] answers from a cassette of canned responses (`--replay`)
+//! so the CLI can run without a network or a Linear workspace, and
+//! [`Recorder`] writes every exchange to a cassette (`--record`).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::client::operation_name;
+use crate::error::{Error, Result};
+
+/// Fields redacted from recorded cassettes in addition to `--redact`.
+const REDACTED_FIELDS: &[&str] = &[
+    "email",
+    "apiKey",
+    "accessToken",
+    "refreshToken",
+    "token",
+    "secret",
+];
+
+const REDACTED: &str = "[redacted]";
+
+/// Response headers worth keeping in a cassette; the rest are dropped.
+const RECORDED_HEADERS: &[&str] = &["retry-after", "x-complexity", "x-ratelimit-"];
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A GraphQL request ready to send.
+pub struct Request {
+    /// Value of the Authorization header
+    pub authorization: String,
+    pub timeout: Duration,
+    /// The `{query, variables}` body
+    pub body: Value,
+}
+
+impl Request {
+    pub fn query(&self) -> &str {
+        self.body["query"].as_str().unwrap_or_default()
+    }
+
+    pub fn variables(&self) -> &Value {
+        &self.body["variables"]
+    }
+}
+
+pub struct Response {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+pub trait Transport: Send + Sync {
+    fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<Response>>;
+}
+
+/// Where requests go, chosen once at startup from `--replay` and `--record`.
+#[derive(Default)]
+pub struct Options {
+    /// Cassette to answer requests from instead of the network
+    pub replay: Option<PathBuf>,
+    /// Cassette to write every exchange to
+    pub record: Option<PathBuf>,
+    /// Extra fields to redact when recording
+    pub redact: Vec<String>,
+}
+
+static OPTIONS: OnceLock<Options> = OnceLock::new();
+
+/// Select the transport options for the rest of the process. Only the first
+/// call takes effect.
+pub fn configure(options: Options) {
+    let _ = OPTIONS.set(options);
+}
+
+fn options() -> &'static Options {
+    OPTIONS.get_or_init(Options::default)
+}
+
+/// Whether requests are answered from a cassette, so no credential is needed.
+pub fn replaying() -> bool {
+    options().replay.is_some()
+}
+
+/// The transport for requests to `url`, as configured with [`configure`].
+pub fn for_url(url: &str) -> Result<Box<dyn Transport>> {
+    let options = options();
+    let transport: Box<dyn Transport> = match &options.replay {
+        Some(path) => Box::new(ReplayTransport::load(path)?),
+        None => Box::new(HttpTransport::new(url)),
+    };
+    Ok(match &options.record {
+        Some(path) => Box::new(Recorder::new(transport, path, &options.redact)),
+        None => transport,
+    })
+}
+
+/// Posts requests to a GraphQL endpoint over HTTP.
+pub struct HttpTransport {
+    http: reqwest::Client,
+    url: String,
+}
+
+impl HttpTransport {
+    pub fn new(url: &str) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        let http = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("failed to build http client");
+
+        Self {
+            http,
+            url: url.to_string(),
+        }
+    }
+}
+
+impl Transport for HttpTransport {
+    fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<Response>> {
+        let pending = self
+            .http
+            .post(&self.url)
+            .header(AUTHORIZATION, &request.authorization)
+            .timeout(request.timeout)
+            .json(&request.body)
+            .send();
+
+        Box::pin(async move {
+            let response = pending.await?;
+            Ok(Response {
+                status: response.status(),
+                headers: response.headers().clone(),
+                body: response.bytes().await?.to_vec(),
+            })
+        })
+    }
+}
+
+/// A cassette: a file of recorded or hand-written request/response pairs.
+#[derive(Default, Deserialize, Serialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// What a request must look like to be answered by an interaction. Every
+/// field that is set has to match.
+#[derive(Default, Deserialize, Serialize)]
+pub struct RecordedRequest {
+    /// Operation name, e.g. `IssuesQuery`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+    /// Exact query text, for anonymous operations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// Variables that must be sent with these values; others are ignored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variables: Option<Value>,
+    /// Request headers, for reference only; credentials are always redacted
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct RecordedResponse {
+    #[serde(default = "ok")]
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    pub body: Value,
+}
+
+fn ok() -> u16 {
+    200
+}
+
+/// Answers requests from a cassette instead of the network.
+///
+/// Matching interactions are served in file order, and the last one keeps
+/// being served once they have all been used, so a fixture for a query that
+/// runs several times only has to be written once.
+pub struct ReplayTransport {
+    interactions: Vec<Interaction>,
+    served: Mutex<Vec<bool>>,
+}
+
+impl ReplayTransport {
+    pub fn new(cassette: Cassette) -> Self {
+        Self {
+            served: Mutex::new(vec![false; cassette.interactions.len()]),
+            interactions: cassette.interactions,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| Error::io(format!("failed to read {}", path.display()), e))?;
+        let cassette = serde_json::from_str(&contents)
+            .map_err(|e| Error::Config(format!("invalid cassette {}: {}", path.display(), e)))?;
+        Ok(Self::new(cassette))
+    }
+
+    fn answer(&self, request: &Request) -> Result<Response> {
+        let operation = operation_name(request.query());
+        let matching: Vec<usize> = (0..self.interactions.len())
+            .filter(|&i| self.interactions[i].request.matches(&operation, request))
+            .collect();
+
+        let mut served = self.served.lock().unwrap();
+        let index = matching
+            .iter()
+            .copied()
+            .find(|&i| !served[i])
+            .or_else(|| matching.last().copied())
+            .ok_or_else(|| {
+                Error::Config(format!(
+                    "no recorded response for {} with variables {}",
+                    operation,
+                    request.variables()
+                ))
+            })?;
+        served[index] = true;
+
+        let response = &self.interactions[index].response;
+        let mut headers = HeaderMap::new();
+        for (name, value) in &response.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+        Ok(Response {
+            status: StatusCode::from_u16(response.status).unwrap_or(StatusCode::OK),
+            headers,
+            body: serde_json::to_vec(&response.body).unwrap_or_default(),
+        })
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<Response>> {
+        let response = self.answer(request);
+        Box::pin(async move { response })
+    }
+}
+
+impl RecordedRequest {
+    fn matches(&self, operation: &str, request: &Request) -> bool {
+        self.operation
+            .as_deref()
+            .is_none_or(|name| name == operation)
+            && self.query.as_deref().is_none_or(|q| q == request.query())
+            && self
+                .variables
+                .as_ref()
+                .is_none_or(|expected| contains(request.variables(), expected))
+    }
+}
+
+/// Whether `actual` has every field of `expected` with the same value.
+fn contains(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => expected
+            .iter()
+            .all(|(key, value)| actual.get(key).is_some_and(|a| contains(a, value))),
+        _ => actual == expected,
+    }
+}
+
+/// Passes requests on to another transport and saves every exchange to a
+/// cassette that [`ReplayTransport`] can replay.
+///
+/// The cassette is rewritten after each response, so it is complete even if
+/// the command fails part way. Credentials are never written, and the value
+/// of any field named in `REDACTED_FIELDS` or `--redact` is replaced, at any
+/// depth of the variables and response data.
+pub struct Recorder {
+    inner: Box<dyn Transport>,
+    path: PathBuf,
+    redact: Vec<String>,
+    cassette: Mutex<Cassette>,
+}
+
+impl Recorder {
+    pub fn new(inner: Box<dyn Transport>, path: &Path, redact: &[String]) -> Self {
+        Self {
+            inner,
+            path: path.to_path_buf(),
+            redact: REDACTED_FIELDS
+                .iter()
+                .map(|f| f.to_string())
+                .chain(redact.iter().cloned())
+                .collect(),
+            cassette: Mutex::new(Cassette::default()),
+        }
+    }
+
+    fn record(&self, request: &Request, response: &Response) -> Result<()> {
+        let mut variables = request.variables().clone();
+        self.scrub(&mut variables);
+        let mut body = serde_json::from_slice(&response.body)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&response.body).into()));
+        self.scrub(&mut body);
+
+        let headers = response
+            .headers
+            .iter()
+            .filter(|(name, _)| {
+                RECORDED_HEADERS
+                    .iter()
+                    .any(|kept| name.as_str().starts_with(kept))
+            })
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+
+        let mut cassette = self.cassette.lock().unwrap();
+        cassette.interactions.push(Interaction {
+            request: RecordedRequest {
+                operation: Some(operation_name(request.query())),
+                query: Some(request.query().to_string()),
+                variables: Some(variables),
+                headers: BTreeMap::from([(AUTHORIZATION.to_string(), REDACTED.to_string())]),
+            },
+            response: RecordedResponse {
+                status: response.status.as_u16(),
+                headers,
+                body,
+            },
+        });
+
+        let contents = serde_json::to_string_pretty(&*cassette).unwrap_or_default();
+        fs::write(&self.path, contents + "\n")
+            .map_err(|e| Error::io(format!("failed to write {}", self.path.display()), e))
+    }
+
+    /// Replace the value of every redacted field in `value`.
+    fn scrub(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if self.redact.iter().any(|f| f.eq_ignore_ascii_case(key)) && !value.is_null() {
+                        *value = Value::String(REDACTED.to_string());
+                    } else {
+                        self.scrub(value);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.scrub(item)),
+            _ => {}
+        }
+    }
+}
+
+impl Transport for Recorder {
+    fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            let response = self.inner.send(request).await?;
+            self.record(request, &response)?;
+            Ok(response)
+        })
+    }
+}
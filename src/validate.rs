@@ -0,0 +1,375 @@
+//! Local validation of hand-written GraphQL documents against the bundled
+//! Linear schema, so mistakes are reported with a line and column before
+//! anything is sent.
+//!
+//! This covers the checks that catch most typos (unknown types, fields and
+//! arguments, missing required arguments, selections on leaf fields and
+//! undeclared variables); argument values are left to the server.
+
+use std::collections::{HashMap, HashSet};
+
+use cynic_parser::common::OperationType;
+use cynic_parser::executable::{Directive, ExecutableDefinition, Selection};
+use cynic_parser::type_system::{Definition, FieldDefinition, TypeDefinition};
+use cynic_parser::{ExecutableDocument, Span};
+
+use crate::error::{Error, Result};
+
+const SCHEMA: &str = include_str!("../schema.graphql");
+
+/// Scalars every schema has without declaring them.
+const BUILTIN_SCALARS: &[&str] = &["String", "Int", "Float", "Boolean", "ID"];
+
+/// Parse `source` and check it against the schema, returning the parsed
+/// document or every problem found.
+pub fn document(source: &str) -> Result<ExecutableDocument> {
+    let document = cynic_parser::parse_executable_document(source).map_err(|e| {
+        let location = e.span().map(|span| position(source, span));
+        invalid(vec![(location, syntax_error(&e))])
+    })?;
+
+    let schema =
+        cynic_parser::parse_type_system_document(SCHEMA).expect("bundled schema is valid GraphQL");
+    let types: HashMap<&str, TypeDefinition> = schema
+        .definitions()
+        .filter_map(|d| match d {
+            Definition::Type(ty) => Some((ty.name(), ty)),
+            _ => None,
+        })
+        .collect();
+
+    let mut validator = Validator {
+        types,
+        fragment_names: document.fragments().map(|f| f.name()).collect(),
+        fragments: HashMap::new(),
+        errors: Vec::new(),
+    };
+    validator.check(&document);
+
+    if validator.errors.is_empty() {
+        Ok(document)
+    } else {
+        validator.errors.sort_by_key(|(span, _)| span.start);
+        let errors = validator
+            .errors
+            .into_iter()
+            .map(|(span, message)| (Some(position(source, span)), message))
+            .collect();
+        Err(invalid(errors))
+    }
+}
+
+/// Variables and fragments referenced from a selection set.
+#[derive(Default)]
+struct Usage<'a> {
+    variables: Vec<(&'a str, Span)>,
+    spreads: Vec<&'a str>,
+}
+
+struct Validator<'a> {
+    types: HashMap<&'a str, TypeDefinition<'a>>,
+    fragment_names: HashSet<&'a str>,
+    /// Usage within each fragment definition, for following spreads
+    fragments: HashMap<&'a str, Usage<'a>>,
+    errors: Vec<(Span, String)>,
+}
+
+impl<'a> Validator<'a> {
+    fn check(&mut self, document: &'a ExecutableDocument) {
+        for fragment in document.fragments() {
+            let mut usage = Usage::default();
+            let on = fragment.type_condition();
+            if self.composite(on, fragment.type_condition_span()) {
+                self.selections(on, fragment.selection_set(), &mut usage);
+            }
+            self.fragments.insert(fragment.name(), usage);
+        }
+
+        let operations = document.operations().count();
+        for definition in document.definitions() {
+            let ExecutableDefinition::Operation(operation) = definition else {
+                continue;
+            };
+            let span = operation
+                .name_span()
+                .unwrap_or(operation.selection_set_span());
+            if operations > 1 {
+                self.error(span, "only one operation per document is supported");
+                return;
+            }
+
+            let root = match operation.operation_type() {
+                OperationType::Query => "Query",
+                OperationType::Mutation => "Mutation",
+                OperationType::Subscription => {
+                    self.error(span, "subscriptions are not supported");
+                    continue;
+                }
+            };
+
+            let mut usage = Usage::default();
+            self.selections(root, operation.selection_set(), &mut usage);
+            self.directives(operation.directives(), &mut usage);
+
+            let mut declared = HashSet::new();
+            for variable in operation.variable_definitions() {
+                let ty = variable.ty();
+                if BUILTIN_SCALARS.contains(&ty.name()) {
+                    declared.insert(variable.name());
+                    continue;
+                }
+                match self.types.get(ty.name()) {
+                    Some(TypeDefinition::Scalar(_))
+                    | Some(TypeDefinition::Enum(_))
+                    | Some(TypeDefinition::InputObject(_)) => {}
+                    Some(_) => self.error(
+                        ty.name_span(),
+                        format!("variable ${} has non-input type {}", variable.name(), ty),
+                    ),
+                    None => self.error(ty.name_span(), format!("unknown type {}", ty.name())),
+                }
+                declared.insert(variable.name());
+            }
+
+            let used = self.used_variables(usage);
+            for (name, span) in &used {
+                if !declared.contains(name) {
+                    self.error(*span, format!("variable ${} is not declared", name));
+                }
+            }
+            for variable in operation.variable_definitions() {
+                if !used.iter().any(|(name, _)| *name == variable.name()) {
+                    self.error(
+                        variable.name_span(),
+                        format!("variable ${} is never used", variable.name()),
+                    );
+                }
+            }
+        }
+    }
+
+    fn selections(
+        &mut self,
+        parent: &'a str,
+        selections: impl Iterator<Item = Selection<'a>>,
+        usage: &mut Usage<'a>,
+    ) {
+        for selection in selections {
+            match selection {
+                Selection::Field(field) => {
+                    self.directives(field.directives(), usage);
+                    for argument in field.arguments() {
+                        collect_variables(argument.value(), usage);
+                    }
+
+                    let name = field.name();
+                    if name == "__typename" {
+                        self.leaf(name, field.selection_set_span(), "String");
+                        continue;
+                    }
+                    if name.starts_with("__") {
+                        // Introspection; not described by the schema file
+                        continue;
+                    }
+
+                    let Some(definition) = self.field(parent, name) else {
+                        let message = match self.types.get(parent) {
+                            Some(TypeDefinition::Union(_)) => format!(
+                                "cannot query field {} on union {}; use an inline fragment",
+                                name, parent
+                            ),
+                            _ => format!("unknown field {} on type {}", name, parent),
+                        };
+                        self.error(field.name_span(), message);
+                        continue;
+                    };
+
+                    for argument in field.arguments() {
+                        if !definition.arguments().any(|a| a.name() == argument.name()) {
+                            self.error(
+                                argument.name_span(),
+                                format!(
+                                    "unknown argument {} on field {}.{}",
+                                    argument.name(),
+                                    parent,
+                                    name
+                                ),
+                            );
+                        }
+                    }
+                    for required in definition.arguments() {
+                        if required.ty().is_non_null()
+                            && required.default_value().is_none()
+                            && !field.arguments().any(|a| a.name() == required.name())
+                        {
+                            self.error(
+                                field.name_span(),
+                                format!(
+                                    "field {}.{} requires argument {}: {}",
+                                    parent,
+                                    name,
+                                    required.name(),
+                                    required.ty()
+                                ),
+                            );
+                        }
+                    }
+
+                    let ty = definition.ty().name();
+                    match self.types.get(ty) {
+                        Some(
+                            TypeDefinition::Object(_)
+                            | TypeDefinition::Interface(_)
+                            | TypeDefinition::Union(_),
+                        ) => {
+                            if field.selection_set().len() == 0 {
+                                self.error(
+                                    field.name_span(),
+                                    format!(
+                                        "field {} of type {} must have a selection of subfields",
+                                        name, ty
+                                    ),
+                                );
+                            } else {
+                                self.selections(ty, field.selection_set(), usage);
+                            }
+                        }
+                        _ => self.leaf(name, field.selection_set_span(), ty),
+                    }
+                }
+                Selection::InlineFragment(fragment) => {
+                    self.directives(fragment.directives(), usage);
+                    let on = match (fragment.type_condition(), fragment.type_condition_span()) {
+                        (Some(on), Some(span)) => {
+                            if !self.composite(on, span) {
+                                continue;
+                            }
+                            on
+                        }
+                        _ => parent,
+                    };
+                    self.selections(on, fragment.selection_set(), usage);
+                }
+                Selection::FragmentSpread(spread) => {
+                    self.directives(spread.directives(), usage);
+                    usage.spreads.push(spread.fragment_name());
+                    if !self.fragment_names.contains(spread.fragment_name()) {
+                        self.error(
+                            spread.fragment_name_span(),
+                            format!("unknown fragment {}", spread.fragment_name()),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn directives(&self, directives: impl Iterator<Item = Directive<'a>>, usage: &mut Usage<'a>) {
+        for directive in directives {
+            for argument in directive.arguments() {
+                collect_variables(argument.value(), usage);
+            }
+        }
+    }
+
+    /// Variables used directly and through (possibly nested) fragment spreads.
+    fn used_variables(&self, usage: Usage<'a>) -> Vec<(&'a str, Span)> {
+        let mut variables = usage.variables;
+        let mut pending = usage.spreads;
+        let mut seen = HashSet::new();
+        while let Some(name) = pending.pop() {
+            if !seen.insert(name) {
+                continue;
+            }
+            if let Some(fragment) = self.fragments.get(name) {
+                variables.extend(fragment.variables.iter().copied());
+                pending.extend(fragment.spreads.iter().copied());
+            }
+        }
+        variables
+    }
+
+    fn field(&self, parent: &str, name: &str) -> Option<FieldDefinition<'a>> {
+        let mut fields = match self.types.get(parent)? {
+            TypeDefinition::Object(object) => object.fields(),
+            TypeDefinition::Interface(interface) => interface.fields(),
+            _ => return None,
+        };
+        fields.find(|f| f.name() == name)
+    }
+
+    /// Check that a type condition names an object, interface or union.
+    fn composite(&mut self, name: &str, span: Span) -> bool {
+        match self.types.get(name) {
+            Some(
+                TypeDefinition::Object(_) | TypeDefinition::Interface(_) | TypeDefinition::Union(_),
+            ) => true,
+            Some(_) => {
+                self.error(
+                    span,
+                    format!("fragment cannot be on non-composite type {}", name),
+                );
+                false
+            }
+            None => {
+                self.error(span, format!("unknown type {}", name));
+                false
+            }
+        }
+    }
+
+    fn leaf(&mut self, name: &str, selection: Option<Span>, ty: &str) {
+        if let Some(span) = selection {
+            self.error(
+                span,
+                format!(
+                    "field {} of type {} cannot have a selection of subfields",
+                    name, ty
+                ),
+            );
+        }
+    }
+
+    fn error(&mut self, span: Span, message: impl Into<String>) {
+        self.errors.push((span, message.into()));
+    }
+}
+
+/// A parse error without the grammar's list of expected tokens, which is
+/// too long to be useful.
+fn syntax_error(error: &cynic_parser::Error) -> String {
+    match error {
+        cynic_parser::Error::UnrecognizedEof { .. } => "unexpected end of document".to_string(),
+        cynic_parser::Error::UnrecognizedToken {
+            token: (_, token, _),
+            ..
+        } => format!("unexpected {}", token),
+        other => other.to_string(),
+    }
+}
+
+fn collect_variables<'a>(value: cynic_parser::Value<'a>, usage: &mut Usage<'a>) {
+    let span = value.span();
+    usage
+        .variables
+        .extend(value.variables_used().map(|name| (name, span)));
+}
+
+/// The 1-based line and column of a span's start.
+fn position(source: &str, span: Span) -> (usize, usize) {
+    let before = &source[..span.start.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, column)
+}
+
+fn invalid(errors: Vec<(Option<(usize, usize)>, String)>) -> Error {
+    let lines: Vec<String> = errors
+        .into_iter()
+        .map(|(location, message)| match location {
+            Some((line, column)) => format!("  line {}, column {}: {}", line, column, message),
+            None => format!("  {}", message),
+        })
+        .collect();
+    Error::Validation(format!("invalid query\n{}", lines.join("\n")))
+}
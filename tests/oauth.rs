@@ -0,0 +1,281 @@
+//! Runs the OAuth login and token refresh against a stand-in authorization
+//! server, playing the browser's part by following the authorize URL the
+//! CLI prints back to its loopback listener.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+type Form = HashMap<String, String>;
+
+/// A token endpoint that answers every request with a fixed token and keeps
+/// the forms it was sent.
+struct TokenServer {
+    url: String,
+    requests: Arc<Mutex<Vec<Form>>>,
+}
+
+impl TokenServer {
+    fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let received = Arc::clone(&requests);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let form = read_form(&mut stream);
+                let body = match form.get("grant_type").map(String::as_str) {
+                    Some("authorization_code") => {
+                        r#"{"access_token": "access-1", "refresh_token": "refresh-1", "expires_in": 3600}"#
+                    }
+                    // Doesn't rotate the refresh token, which must be kept
+                    _ => r#"{"access_token": "access-2", "expires_in": 3600}"#,
+                };
+                received.lock().unwrap().push(form);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+            }
+        });
+        Self { url, requests }
+    }
+
+    fn requests(&self) -> Vec<Form> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// Read a form-encoded POST body, going by its `Content-Length`.
+fn read_form(stream: &mut TcpStream) -> Form {
+    let mut reader = BufReader::new(stream);
+    let mut length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; length];
+    let _ = reader.read_exact(&mut body);
+    url::form_urlencoded::parse(&body).into_owned().collect()
+}
+
+/// A home directory with a profile using the token server's OAuth app.
+struct Home {
+    dir: tempfile::TempDir,
+}
+
+impl Home {
+    fn new(server: &TokenServer) -> Self {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let config = dir.path().join("config/linear");
+        fs::create_dir_all(&config).unwrap();
+        fs::write(
+            config.join("config.toml"),
+            format!(
+                "[profiles.default.oauth]\n\
+                 client_id = \"test-client\"\n\
+                 authorize_url = \"{url}/authorize\"\n\
+                 token_url = \"{url}/token\"\n\
+                 redirect_port = 0\n",
+                url = server.url
+            ),
+        )
+        .unwrap();
+        Self { dir }
+    }
+
+    fn credentials(&self) -> PathBuf {
+        self.dir.path().join("config/linear/credentials.toml")
+    }
+
+    fn command(&self, cassette: &str, args: &[&str]) -> Command {
+        let home = self.dir.path();
+        let mut command = Command::new(env!("CARGO_BIN_EXE_linear"));
+        command
+            .args(args)
+            .env_clear()
+            .env("HOME", home)
+            .env("XDG_CONFIG_HOME", home.join("config"))
+            .env("XDG_CACHE_HOME", home.join("cache"))
+            .env("XDG_DATA_HOME", home.join("data"))
+            .env(
+                "LINEAR_REPLAY",
+                Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join("tests/cassettes")
+                    .join(format!("{}.json", cassette)),
+            )
+            .env("NO_COLOR", "1");
+        command
+    }
+}
+
+/// Wait for the authorize URL on the child's stderr, returning it and the
+/// rest of stderr once the child is done with it.
+fn authorize_url(child: &mut Child) -> (url::Url, thread::JoinHandle<String>) {
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut seen = String::new();
+    loop {
+        let mut line = String::new();
+        if stderr.read_line(&mut line).unwrap() == 0 {
+            panic!("no authorize URL printed:\n{}", seen);
+        }
+        if let Ok(url) = url::Url::parse(line.trim()) {
+            let rest = thread::spawn(move || {
+                let mut rest = String::new();
+                let _ = stderr.read_to_string(&mut rest);
+                rest
+            });
+            return (url, rest);
+        }
+        seen.push_str(&line);
+    }
+}
+
+/// Play the browser coming back from the authorization server.
+fn redirect(redirect_uri: &str, query: &str) -> String {
+    let url = url::Url::parse(redirect_uri).unwrap();
+    let mut stream =
+        TcpStream::connect(("127.0.0.1", url.port().unwrap())).expect("failed to connect");
+    // In one write, as the listener reads the request in one go
+    let request = format!(
+        "GET {}?{} HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        url.path(),
+        query
+    );
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    let _ = stream.read_to_string(&mut response);
+    response
+}
+
+/// Start `auth login --oauth` and wait for it to print the authorize URL.
+fn login(home: &Home) -> (Child, url::Url, thread::JoinHandle<String>) {
+    let mut child = home
+        .command("workspace", &["auth", "login", "--oauth"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run linear");
+    let (authorize, stderr) = authorize_url(&mut child);
+    (child, authorize, stderr)
+}
+
+fn param(url: &url::Url, name: &str) -> String {
+    url.query_pairs()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.into_owned())
+        .unwrap_or_else(|| panic!("{} has no {}", url, name))
+}
+
+#[test]
+fn login_exchanges_code_with_pkce() {
+    let server = TokenServer::start();
+    let home = Home::new(&server);
+    let (child, authorize, stderr) = login(&home);
+    assert_eq!(authorize.path(), "/authorize");
+    assert_eq!(param(&authorize, "client_id"), "test-client");
+    assert_eq!(param(&authorize, "code_challenge_method"), "S256");
+
+    let redirect_uri = param(&authorize, "redirect_uri");
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair("code", "code-1")
+        .append_pair("state", &param(&authorize, "state"))
+        .finish();
+    let page = redirect(&redirect_uri, &query);
+    assert!(page.contains("Authorization complete"), "{}", page);
+
+    let output = child.wait_with_output().expect("failed to wait for linear");
+    let stderr = stderr.join().unwrap();
+    assert!(output.status.success(), "{}", stderr);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("as Ada Lovelace"));
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    let form = &requests[0];
+    assert_eq!(form["grant_type"], "authorization_code");
+    assert_eq!(form["code"], "code-1");
+    assert_eq!(form["client_id"], "test-client");
+    assert_eq!(form["redirect_uri"], redirect_uri);
+    assert_eq!(
+        URL_SAFE_NO_PAD.encode(Sha256::digest(form["code_verifier"].as_bytes())),
+        param(&authorize, "code_challenge")
+    );
+
+    let credentials = fs::read_to_string(home.credentials()).unwrap();
+    assert!(credentials.contains("access_token = \"access-1\""));
+    assert!(credentials.contains("refresh_token = \"refresh-1\""));
+}
+
+#[test]
+fn login_rejects_mismatched_state() {
+    let server = TokenServer::start();
+    let home = Home::new(&server);
+    let (mut child, authorize, stderr) = login(&home);
+
+    let page = redirect(
+        &param(&authorize, "redirect_uri"),
+        "code=forged&state=wrong",
+    );
+    assert!(page.contains("Authorization failed"), "{}", page);
+    assert!(!child.wait().expect("failed to wait for linear").success());
+    assert!(stderr.join().unwrap().contains("state mismatch"));
+    assert!(server.requests().is_empty());
+    assert!(!home.credentials().exists());
+}
+
+#[test]
+fn expired_token_is_refreshed_and_saved() {
+    let server = TokenServer::start();
+    let home = Home::new(&server);
+    fs::write(
+        home.credentials(),
+        "[profiles.default]\n\
+         access_token = \"expired\"\n\
+         refresh_token = \"refresh-1\"\n\
+         expires_at = 1\n",
+    )
+    .unwrap();
+
+    // The cassette answers the first request with a 401, as Linear does for
+    // an expired token, and the retry with the viewer
+    let output = home
+        .command("oauth_refresh", &["user", "me"])
+        .output()
+        .expect("failed to run linear");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Ada Lovelace"));
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0]["grant_type"], "refresh_token");
+    assert_eq!(requests[0]["refresh_token"], "refresh-1");
+    assert_eq!(requests[0]["client_id"], "test-client");
+
+    let credentials = fs::read_to_string(home.credentials()).unwrap();
+    assert!(credentials.contains("access_token = \"access-2\""));
+    assert!(credentials.contains("refresh_token = \"refresh-1\""));
+}
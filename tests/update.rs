@@ -0,0 +1,237 @@
+//! Runs `linear update` against a stand-in for the GitHub API, on a copy of
+//! the binary in a temporary directory so the one under test is never
+//! replaced.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::thread;
+
+use insta::assert_snapshot;
+use sha2::{Digest, Sha256};
+
+const ASSETS: &[&str] = &[
+    "linear-macos-aarch64",
+    "linear-macos-x86_64",
+    "linear-linux-x86_64",
+    "linear-linux-aarch64",
+    "linear-windows-x86_64.exe",
+];
+
+/// Bytes served as the new release's binary for every platform.
+const NEW_BINARY: &[u8] = b"linear v99.0.0";
+
+/// A minimal HTTP server answering GETs from a fixed set of routes.
+struct GitHub {
+    url: String,
+}
+
+impl GitHub {
+    fn serve(routes: impl Fn(&str) -> HashMap<String, (u16, Vec<u8>)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let routes = Arc::new(routes(&url));
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut request = Vec::new();
+                let mut buffer = [0; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buffer) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buffer[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request);
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let (status, body) = routes
+                    .get(path)
+                    .cloned()
+                    .unwrap_or((404, b"{\"message\":\"Not Found\"}".to_vec()));
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {} -\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                );
+                let _ = stream.write_all(&body);
+            }
+        });
+        Self { url }
+    }
+
+    /// Publish v99.0.0 with `checksum` as the checksum of every asset.
+    fn release(checksum: String) -> Self {
+        Self::serve(|url| {
+            let assets: Vec<String> = ASSETS
+                .iter()
+                .flat_map(|name| {
+                    [
+                        asset(url, name, "/download/binary"),
+                        asset(url, &format!("{}.sha256", name), "/download/checksum"),
+                    ]
+                })
+                .collect();
+            let release = format!(
+                r#"{{"tag_name": "v99.0.0", "prerelease": false, "assets": [{}]}}"#,
+                assets.join(",")
+            );
+            HashMap::from([
+                (
+                    "/repos/nickcramaro/linear-cli/releases/latest".to_string(),
+                    (200, release.into_bytes()),
+                ),
+                ("/download/binary".to_string(), (200, NEW_BINARY.to_vec())),
+                (
+                    "/download/checksum".to_string(),
+                    (200, format!("{}  linear\n", checksum).into_bytes()),
+                ),
+            ])
+        })
+    }
+}
+
+fn asset(url: &str, name: &str, path: &str) -> String {
+    format!(
+        r#"{{"name": "{}", "browser_download_url": "{}{}"}}"#,
+        name, url, path
+    )
+}
+
+fn sha256(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// A copy of the binary that updates itself from `github`.
+struct Install {
+    dir: tempfile::TempDir,
+    github: String,
+}
+
+struct Run {
+    code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+impl Install {
+    fn new(github: &GitHub) -> Self {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        fs::copy(env!("CARGO_BIN_EXE_linear"), dir.path().join("linear"))
+            .expect("failed to copy linear");
+        Self {
+            dir,
+            github: github.url.clone(),
+        }
+    }
+
+    fn exe(&self) -> PathBuf {
+        self.dir.path().join("linear")
+    }
+
+    fn backup(&self) -> PathBuf {
+        self.dir.path().join("linear.old")
+    }
+
+    fn run(&self, args: &[&str]) -> Run {
+        let output = Command::new(self.exe())
+            .arg("update")
+            .args(args)
+            .env_clear()
+            .env("HOME", self.dir.path())
+            .env("LINEAR_GITHUB_API_URL", &self.github)
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("failed to run linear");
+
+        let scrub = |bytes: Vec<u8>| {
+            let mut text = String::from_utf8_lossy(&bytes)
+                .replace(&*self.dir.path().to_string_lossy(), "[DIR]")
+                .replace(&self.github, "[GITHUB]")
+                .replace(env!("CARGO_PKG_VERSION"), "[VERSION]");
+            for name in ASSETS {
+                text = text.replace(name, "[ASSET]");
+            }
+            text
+        };
+        Run {
+            code: output.status.code().unwrap_or(-1),
+            stdout: scrub(output.stdout),
+            stderr: scrub(output.stderr),
+        }
+    }
+}
+
+impl fmt::Display for Run {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "exit code: {}", self.code)?;
+        if !self.stdout.is_empty() {
+            write!(f, "--- stdout\n{}", self.stdout)?;
+        }
+        if !self.stderr.is_empty() {
+            write!(f, "--- stderr\n{}", self.stderr)?;
+        }
+        Ok(())
+    }
+}
+
+fn contents(path: &Path) -> Vec<u8> {
+    fs::read(path).expect("failed to read binary")
+}
+
+#[test]
+fn check() {
+    let github = GitHub::release(sha256(NEW_BINARY));
+    assert_snapshot!("update_check", Install::new(&github).run(&["--check"]));
+}
+
+#[test]
+fn install_verifies_checksum_and_keeps_backup() {
+    let github = GitHub::release(sha256(NEW_BINARY));
+    let install = Install::new(&github);
+    let original = contents(&install.exe());
+
+    assert_snapshot!("update_install", install.run(&[]));
+    assert_eq!(contents(&install.exe()), NEW_BINARY);
+    assert_eq!(contents(&install.backup()), original);
+}
+
+#[test]
+fn checksum_mismatch_leaves_binary_alone() {
+    let github = GitHub::release(sha256(b"something else"));
+    let install = Install::new(&github);
+    let original = contents(&install.exe());
+
+    let run = install.run(&[]);
+    assert_eq!(run.code, 8, "{}", run);
+    assert!(run.stderr.contains("checksum mismatch"), "{}", run);
+    assert_eq!(contents(&install.exe()), original);
+    assert!(!install.backup().exists());
+}
+
+#[test]
+fn rollback_swaps_with_backup() {
+    let github = GitHub::release(sha256(NEW_BINARY));
+    let install = Install::new(&github);
+    let original = contents(&install.exe());
+    fs::write(install.backup(), b"linear v0.1.0").unwrap();
+
+    assert_snapshot!("update_rollback", install.run(&["--rollback"]));
+    assert_eq!(contents(&install.exe()), b"linear v0.1.0");
+    assert_eq!(contents(&install.backup()), original);
+}
+
+#[test]
+fn unknown_version() {
+    let github = GitHub::release(sha256(NEW_BINARY));
+    assert_snapshot!(
+        "update_unknown_version",
+        Install::new(&github).run(&["--version", "0.0.1"])
+    );
+}